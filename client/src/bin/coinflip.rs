@@ -0,0 +1,210 @@
+//! `coinflip`: create, flip and toss coins on a coin-flipper node from the command line.
+
+use clap::Parser;
+use coin_flipper_client::{Client, CoinEvent, CoinSide, Included};
+use node_template_runtime::{AccountId, Balance, BlockNumber};
+use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+
+#[derive(Debug, clap::Parser)]
+#[command(name = "coinflip", about = "Play with coins on a coin-flipper node.")]
+struct Cli {
+	/// WebSocket RPC endpoint of the node.
+	#[arg(long, default_value = "ws://127.0.0.1:9944")]
+	url: String,
+
+	/// Secret URI of the signing account, e.g. a dev key like `//Alice`.
+	#[arg(long, default_value = "//Alice")]
+	suri: String,
+
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+	/// Create a coin for the signing account.
+	Create,
+	/// Flip the coin of the signing account, or of an owner that approved it as operator.
+	Flip {
+		/// SS58 address of the coin's owner.
+		#[arg(long)]
+		owner: Option<String>,
+	},
+	/// Toss the coin of the signing account, or of an owner that approved it as operator.
+	Toss {
+		/// SS58 address of the coin's owner.
+		#[arg(long)]
+		owner: Option<String>,
+	},
+	/// Approve an operator to flip and toss the coin of the signing account.
+	Approve {
+		/// SS58 address of the operator.
+		operator: String,
+		/// Last block in which the operator may act, by default it never expires.
+		#[arg(long)]
+		expiry: Option<BlockNumber>,
+	},
+	/// Revoke an operator of the coin of the signing account.
+	Revoke {
+		/// SS58 address of the operator.
+		operator: String,
+	},
+	/// Toss the coin of the signing account every few blocks.
+	Schedule {
+		/// Blocks between two tosses.
+		#[arg(long)]
+		every: BlockNumber,
+		/// Number of tosses.
+		#[arg(long)]
+		count: u32,
+	},
+	/// Cancel the remaining tosses of a schedule of the signing account.
+	CancelSchedule {
+		/// Identifier of the schedule.
+		id: u32,
+	},
+	/// Deposit into the pot in exchange for bankroll shares.
+	Deposit {
+		/// Balance to deposit.
+		amount: Balance,
+	},
+	/// Redeem bankroll shares for their part of the pot.
+	Withdraw {
+		/// Number of shares to redeem.
+		shares: Balance,
+	},
+	/// Pause the play of the signing account for a number of blocks.
+	CoolOff {
+		/// Blocks during which the account cannot flip or toss.
+		duration: BlockNumber,
+	},
+	/// Exclude the signing account from play for at least a number of blocks.
+	SelfExclude {
+		/// Blocks before the exclusion can be lifted.
+		duration: BlockNumber,
+	},
+	/// Lift the self-exclusion of the signing account once its term is over.
+	LiftExclusion,
+	/// Set the loss limits of the signing account, by default no limits.
+	LossLimits {
+		/// Losses allowed in any day.
+		#[arg(long)]
+		daily: Option<Balance>,
+		/// Losses allowed in any week.
+		#[arg(long)]
+		weekly: Option<Balance>,
+	},
+	/// Bet on the side the coin of the signing account lands on when the bet is settled.
+	Bet {
+		/// The side to bet on, `head` or `tail`.
+		side: String,
+		/// Balance at stake.
+		stake: Balance,
+	},
+	/// Show the coin of an account, by default the signing account.
+	Show {
+		/// SS58 address of the account.
+		account: Option<String>,
+	},
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+	let cli = Cli::parse();
+	let signer = sr25519::Pair::from_string(&cli.suri, None)
+		.map_err(|e| format!("Invalid secret URI {}: {:?}", cli.suri, e))?;
+	let client = Client::connect(&cli.url).await?;
+
+	let included = match cli.command {
+		Command::Create => client.create_coin(&signer).await?,
+		Command::Flip { owner: None } => client.flip(&signer).await?,
+		Command::Flip { owner: Some(owner) } =>
+			client.flip_for(&signer, parse_account(&owner)?).await?,
+		Command::Toss { owner: None } => client.toss(&signer).await?,
+		Command::Toss { owner: Some(owner) } =>
+			client.toss_for(&signer, parse_account(&owner)?).await?,
+		Command::Approve { operator, expiry } =>
+			client.approve_operator(&signer, parse_account(&operator)?, expiry).await?,
+		Command::Revoke { operator } =>
+			client.revoke_operator(&signer, parse_account(&operator)?).await?,
+		Command::Schedule { every, count } => client.schedule_tosses(&signer, every, count).await?,
+		Command::CancelSchedule { id } => client.cancel_toss_schedule(&signer, id).await?,
+		Command::Deposit { amount } => client.deposit_bankroll(&signer, amount).await?,
+		Command::Withdraw { shares } => client.withdraw_bankroll(&signer, shares).await?,
+		Command::CoolOff { duration } => client.cool_off(&signer, duration).await?,
+		Command::SelfExclude { duration } => client.self_exclude(&signer, duration).await?,
+		Command::LiftExclusion => client.lift_self_exclusion(&signer).await?,
+		Command::LossLimits { daily, weekly } =>
+			client.set_loss_limits(&signer, daily, weekly).await?,
+		Command::Bet { side, stake } =>
+			client.place_bet(&signer, parse_side(&side)?, stake).await?,
+		Command::Show { account } => {
+			let account = match account {
+				Some(address) => parse_account(&address)?,
+				None => AccountId::from(signer.public()),
+			};
+			match client.coin(&account, None).await? {
+				Some(coin) => println!("{}: {:?}", account, coin.side()),
+				None => println!("{}: no coin", account),
+			}
+			return Ok(())
+		},
+	};
+
+	print_included(&included);
+	Ok(())
+}
+
+fn parse_account(address: &str) -> Result<AccountId, String> {
+	AccountId::from_ss58check(address).map_err(|e| format!("Invalid address {}: {:?}", address, e))
+}
+
+fn parse_side(side: &str) -> Result<CoinSide, String> {
+	match side.to_lowercase().as_str() {
+		"head" => Ok(CoinSide::Head),
+		"tail" => Ok(CoinSide::Tail),
+		_ => Err(format!("Invalid side {}, expected head or tail", side)),
+	}
+}
+
+fn print_included(included: &Included) {
+	println!("included in block {:?} at index {}", included.block_hash, included.extrinsic_index);
+	for event in &included.events {
+		match event {
+			CoinEvent::CoinCreated { who } => println!("coin created for {}", who),
+			CoinEvent::CoinFlipped { who, side } => println!("{} flipped {:?}", who, side),
+			CoinEvent::CoinTossed { who, side, nonce } =>
+				println!("{} tossed {:?} (nonce {})", who, side, nonce),
+			CoinEvent::CoinRemoved { who } => println!("coin of {} removed", who),
+			CoinEvent::CoinExpired { who } => println!("coin of {} expired", who),
+			CoinEvent::OperatorApproved { owner, operator, expiry: Some(expiry) } =>
+				println!("{} approved operator {} until block {}", owner, operator, expiry),
+			CoinEvent::OperatorApproved { owner, operator, expiry: None } =>
+				println!("{} approved operator {}", owner, operator),
+			CoinEvent::OperatorRevoked { owner, operator } =>
+				println!("{} revoked operator {}", owner, operator),
+			CoinEvent::TossesScheduled { who, id, every, count } => println!(
+				"{} scheduled {} tosses every {} blocks (schedule {})",
+				who, count, every, id
+			),
+			CoinEvent::TossScheduleCancelled { who, id } =>
+				println!("{} cancelled schedule {}", who, id),
+			CoinEvent::BankrollDeposited { who, amount, shares } =>
+				println!("{} deposited {} for {} shares", who, amount, shares),
+			CoinEvent::BankrollWithdrawn { who, shares, amount } =>
+				println!("{} redeemed {} shares for {}", who, shares, amount),
+			CoinEvent::CoolingOffStarted { who, until } =>
+				println!("{} cools off until block {}", who, until),
+			CoinEvent::SelfExcluded { who, until } =>
+				println!("{} excluded, can be lifted from block {}", who, until),
+			CoinEvent::SelfExclusionLifted { who } => println!("{} lifted its self-exclusion", who),
+			CoinEvent::BetPlaced { who, side, stake, payout, settle_at } => println!(
+				"{} bet {} on {:?} to win {}, settled in block {}",
+				who, stake, side, payout, settle_at
+			),
+			CoinEvent::BetSettled { who, side, payout } =>
+				println!("{} landed {:?}, bet paid out {}", who, side, payout),
+			event => println!("{:?}", event),
+		}
+	}
+}
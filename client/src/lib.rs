@@ -0,0 +1,492 @@
+//! A typed client for the coin-flipper chain.
+//!
+//! Calls, storage values and events are the runtime's own types, taken from the
+//! `node-template-runtime` crate this client is built against, so they are always SCALE
+//! compatible with that runtime. Unlike subxt, the client does not generate these types from
+//! metadata: the runtime crate is part of this workspace, so its types are the source the metadata
+//! is generated from in the first place. Instead, [`Client::connect`] checks the node's metadata
+//! against the metadata of the runtime it was built against, and refuses to talk to a node whose
+//! `spec_version` or metadata differ, since its types may have changed.
+
+#![warn(missing_docs)]
+
+use std::fmt;
+
+use codec::{Decode, Encode};
+use frame_support::{storage::storage_prefix, Blake2_128Concat, StorageHasher};
+use frame_system::EventRecord;
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use node_template_runtime::{
+	opaque, pallet_template, AccountId, Balance, BlockNumber, Hash, Header, Nonce, Runtime,
+	RuntimeCall, RuntimeEvent, SignedExtra, SignedPayload, UncheckedExtrinsic, VERSION,
+};
+use sc_rpc_api::{author::AuthorApiClient, chain::ChainApiClient, state::StateApiClient};
+use sc_transaction_pool_api::TransactionStatus;
+use sp_core::{
+	sr25519,
+	storage::{StorageData, StorageKey},
+	Bytes, Pair,
+};
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+use sp_runtime::{generic::SignedBlock, DispatchError};
+use sp_version::RuntimeVersion;
+use substrate_frame_rpc_system::SystemApiClient;
+
+pub use node_template_runtime::pallet_template::{Coin, CoinSide};
+
+/// An event of the coin-flipper pallet.
+pub type CoinEvent = pallet_template::Event<Runtime>;
+
+/// Typed builders of the coin-flipper calls.
+pub mod calls {
+	use super::*;
+
+	/// Builds a `create_coin` call.
+	pub fn create_coin() -> RuntimeCall {
+		pallet_template::Call::<Runtime>::create_coin {}.into()
+	}
+
+	/// Builds a `do_flip` call.
+	pub fn do_flip() -> RuntimeCall {
+		pallet_template::Call::<Runtime>::do_flip {}.into()
+	}
+
+	/// Builds a `do_toss` call.
+	pub fn do_toss() -> RuntimeCall {
+		pallet_template::Call::<Runtime>::do_toss {}.into()
+	}
+
+	/// Builds an `approve_operator` call.
+	pub fn approve_operator(operator: AccountId, expiry: Option<BlockNumber>) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::approve_operator { who: operator, expiry }.into()
+	}
+
+	/// Builds a `revoke_operator` call.
+	pub fn revoke_operator(operator: AccountId) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::revoke_operator { who: operator }.into()
+	}
+
+	/// Builds a `flip_for` call.
+	pub fn flip_for(owner: AccountId) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::flip_for { owner }.into()
+	}
+
+	/// Builds a `toss_for` call.
+	pub fn toss_for(owner: AccountId) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::toss_for { owner }.into()
+	}
+
+	/// Builds a `schedule_tosses` call.
+	pub fn schedule_tosses(every: BlockNumber, count: u32) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::schedule_tosses { every, count }.into()
+	}
+
+	/// Builds a `cancel_toss_schedule` call.
+	pub fn cancel_toss_schedule(id: u32) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::cancel_toss_schedule { id }.into()
+	}
+
+	/// Builds a `deposit_bankroll` call.
+	pub fn deposit_bankroll(amount: Balance) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::deposit_bankroll { amount }.into()
+	}
+
+	/// Builds a `withdraw_bankroll` call.
+	pub fn withdraw_bankroll(shares: Balance) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::withdraw_bankroll { shares }.into()
+	}
+
+	/// Builds a `cool_off` call.
+	pub fn cool_off(duration: BlockNumber) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::cool_off { duration }.into()
+	}
+
+	/// Builds a `self_exclude` call.
+	pub fn self_exclude(duration: BlockNumber) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::self_exclude { duration }.into()
+	}
+
+	/// Builds a `lift_self_exclusion` call.
+	pub fn lift_self_exclusion() -> RuntimeCall {
+		pallet_template::Call::<Runtime>::lift_self_exclusion {}.into()
+	}
+
+	/// Builds a `set_loss_limits` call.
+	pub fn set_loss_limits(daily: Option<Balance>, weekly: Option<Balance>) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::set_loss_limits { daily, weekly }.into()
+	}
+
+	/// Builds a `place_bet` call.
+	pub fn place_bet(side: CoinSide, stake: Balance) -> RuntimeCall {
+		pallet_template::Call::<Runtime>::place_bet { side, stake }.into()
+	}
+}
+
+/// Errors returned by the [`Client`].
+#[derive(Debug)]
+pub enum Error {
+	/// The RPC request failed.
+	Rpc(jsonrpsee::core::Error),
+	/// A value returned by the node could not be decoded.
+	Codec(codec::Error),
+	/// The node runs a runtime this client was not built for.
+	IncompatibleRuntime {
+		/// The `spec_version` this client was built for.
+		expected: u32,
+		/// The `spec_version` of the node.
+		found: u32,
+	},
+	/// The node's metadata differs from the metadata of the runtime this client was built for,
+	/// even though both have the same `spec_version`.
+	IncompatibleMetadata,
+	/// The transaction was not included in a block.
+	NotIncluded(String),
+	/// The transaction was included, but failed.
+	Dispatch(DispatchError),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Rpc(e) => write!(f, "RPC error: {}", e),
+			Error::Codec(e) => write!(f, "Decoding error: {}", e),
+			Error::IncompatibleRuntime { expected, found } => write!(
+				f,
+				"Incompatible runtime: built for spec_version {}, node runs {}",
+				expected, found
+			),
+			Error::IncompatibleMetadata => write!(
+				f,
+				"Incompatible runtime: the node's metadata differs from the runtime this client was built for"
+			),
+			Error::NotIncluded(status) => write!(f, "Transaction not included: {}", status),
+			Error::Dispatch(e) => write!(f, "Transaction failed: {:?}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<jsonrpsee::core::Error> for Error {
+	fn from(e: jsonrpsee::core::Error) -> Self {
+		Error::Rpc(e)
+	}
+}
+
+impl From<codec::Error> for Error {
+	fn from(e: codec::Error) -> Self {
+		Error::Codec(e)
+	}
+}
+
+/// Result type of the [`Client`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A transaction included in a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Included {
+	/// Hash of the block that includes the transaction.
+	pub block_hash: Hash,
+	/// Index of the transaction in the block.
+	pub extrinsic_index: u32,
+	/// The coin-flipper events the transaction emitted.
+	pub events: Vec<CoinEvent>,
+}
+
+/// A client of a coin-flipper node, talking to it over WebSocket RPC.
+pub struct Client {
+	rpc: WsClient,
+	genesis_hash: Hash,
+	version: RuntimeVersion,
+}
+
+impl Client {
+	/// Connects to the node at `url`, e.g. `ws://127.0.0.1:9944`.
+	pub async fn connect(url: &str) -> Result<Self> {
+		let rpc = WsClientBuilder::default().build(url).await?;
+
+		let version = StateApiClient::<Hash>::runtime_version(&rpc, None).await?;
+		if version.spec_version != VERSION.spec_version {
+			return Err(Error::IncompatibleRuntime {
+				expected: VERSION.spec_version,
+				found: version.spec_version,
+			})
+		}
+		let metadata = StateApiClient::<Hash>::metadata(&rpc, None).await?;
+		if metadata.0 != Runtime::metadata().encode() {
+			return Err(Error::IncompatibleMetadata)
+		}
+
+		let genesis =
+			ChainApiClient::<BlockNumber, Hash, Header, SignedBlock<opaque::Block>>::block_hash(
+				&rpc,
+				Some(ListOrValue::Value(NumberOrHex::Number(0))),
+			)
+			.await?;
+		let ListOrValue::Value(Some(genesis_hash)) = genesis else {
+			return Err(jsonrpsee::core::Error::Custom("the node has no genesis block".into()).into())
+		};
+
+		Ok(Self { rpc, genesis_hash, version })
+	}
+
+	/// Returns the next nonce of `account`, including its transactions in the pool.
+	pub async fn nonce(&self, account: &AccountId) -> Result<Nonce> {
+		Ok(SystemApiClient::<Hash, AccountId, Nonce>::nonce(&self.rpc, account.clone()).await?)
+	}
+
+	/// Signs `call` with `signer` and its next nonce.
+	///
+	/// The transaction is immortal and pays no tip.
+	pub async fn sign(
+		&self,
+		signer: &sr25519::Pair,
+		call: RuntimeCall,
+	) -> Result<UncheckedExtrinsic> {
+		let account = AccountId::from(signer.public());
+		let nonce = self.nonce(&account).await?;
+
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(sp_runtime::generic::Era::Immortal),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+			pallet_template::CheckTossRate::<Runtime>::new(),
+		);
+
+		let raw_payload = SignedPayload::from_raw(
+			call.clone(),
+			extra.clone(),
+			(
+				(),
+				self.version.spec_version,
+				self.version.transaction_version,
+				self.genesis_hash,
+				self.genesis_hash,
+				(),
+				(),
+				(),
+				(),
+			),
+		);
+		let signature = raw_payload.using_encoded(|e| signer.sign(e));
+
+		Ok(UncheckedExtrinsic::new_signed(
+			call,
+			account.into(),
+			node_template_runtime::Signature::Sr25519(signature),
+			extra,
+		))
+	}
+
+	/// Signs and submits `call`, then waits until it is included in a block.
+	///
+	/// Fails with [`Error::Dispatch`] if the call was included but failed.
+	pub async fn submit(&self, signer: &sr25519::Pair, call: RuntimeCall) -> Result<Included> {
+		let extrinsic = self.sign(signer, call).await?.encode();
+
+		let mut statuses =
+			AuthorApiClient::<Hash, Hash>::watch_extrinsic(&self.rpc, Bytes(extrinsic.clone()))
+				.await?;
+		let block_hash = loop {
+			match statuses.next().await {
+				Some(Ok(TransactionStatus::InBlock(hash) | TransactionStatus::Finalized(hash))) =>
+					break hash,
+				Some(Ok(
+					TransactionStatus::Future |
+					TransactionStatus::Ready |
+					TransactionStatus::Broadcast(_) |
+					TransactionStatus::Retracted(_),
+				)) => continue,
+				Some(Ok(status)) => return Err(Error::NotIncluded(format!("{:?}", status))),
+				Some(Err(e)) => return Err(e.into()),
+				None => return Err(Error::NotIncluded("subscription closed".into())),
+			}
+		};
+		let _ = statuses.unsubscribe().await;
+
+		let block = ChainApiClient::<BlockNumber, Hash, Header, SignedBlock<opaque::Block>>::block(
+			&self.rpc,
+			Some(block_hash),
+		)
+		.await?
+		.ok_or_else(|| Error::NotIncluded(format!("block {:?} not found", block_hash)))?;
+		let extrinsic_index = block
+			.block
+			.extrinsics
+			.iter()
+			.position(|xt| xt.encode() == extrinsic)
+			.ok_or_else(|| Error::NotIncluded(format!("not found in block {:?}", block_hash)))?
+			as u32;
+
+		let mut events = Vec::new();
+		for record in self.events(block_hash).await? {
+			if record.phase != frame_system::Phase::ApplyExtrinsic(extrinsic_index) {
+				continue
+			}
+			match record.event {
+				RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+					dispatch_error,
+					..
+				}) => return Err(Error::Dispatch(dispatch_error)),
+				RuntimeEvent::TemplateModule(event) => events.push(event),
+				_ => {},
+			}
+		}
+
+		Ok(Included { block_hash, extrinsic_index, events })
+	}
+
+	/// Creates a coin for `signer`.
+	pub async fn create_coin(&self, signer: &sr25519::Pair) -> Result<Included> {
+		self.submit(signer, calls::create_coin()).await
+	}
+
+	/// Flips the coin of `signer`.
+	pub async fn flip(&self, signer: &sr25519::Pair) -> Result<Included> {
+		self.submit(signer, calls::do_flip()).await
+	}
+
+	/// Tosses the coin of `signer`.
+	pub async fn toss(&self, signer: &sr25519::Pair) -> Result<Included> {
+		self.submit(signer, calls::do_toss()).await
+	}
+
+	/// Approves `operator` to flip and toss the coin of `signer` until block `expiry`, or forever.
+	pub async fn approve_operator(
+		&self,
+		signer: &sr25519::Pair,
+		operator: AccountId,
+		expiry: Option<BlockNumber>,
+	) -> Result<Included> {
+		self.submit(signer, calls::approve_operator(operator, expiry)).await
+	}
+
+	/// Revokes `operator` of the coin of `signer`.
+	pub async fn revoke_operator(
+		&self,
+		signer: &sr25519::Pair,
+		operator: AccountId,
+	) -> Result<Included> {
+		self.submit(signer, calls::revoke_operator(operator)).await
+	}
+
+	/// Flips the coin of `owner`, signed by one of its operators.
+	pub async fn flip_for(&self, operator: &sr25519::Pair, owner: AccountId) -> Result<Included> {
+		self.submit(operator, calls::flip_for(owner)).await
+	}
+
+	/// Tosses the coin of `owner`, signed by one of its operators.
+	pub async fn toss_for(&self, operator: &sr25519::Pair, owner: AccountId) -> Result<Included> {
+		self.submit(operator, calls::toss_for(owner)).await
+	}
+
+	/// Schedules `count` tosses of the coin of `signer`, one every `every` blocks.
+	pub async fn schedule_tosses(
+		&self,
+		signer: &sr25519::Pair,
+		every: BlockNumber,
+		count: u32,
+	) -> Result<Included> {
+		self.submit(signer, calls::schedule_tosses(every, count)).await
+	}
+
+	/// Cancels the remaining tosses of the toss schedule `id` of `signer`.
+	pub async fn cancel_toss_schedule(&self, signer: &sr25519::Pair, id: u32) -> Result<Included> {
+		self.submit(signer, calls::cancel_toss_schedule(id)).await
+	}
+
+	/// Deposits `amount` of `signer` into the pot in exchange for bankroll shares.
+	pub async fn deposit_bankroll(
+		&self,
+		signer: &sr25519::Pair,
+		amount: Balance,
+	) -> Result<Included> {
+		self.submit(signer, calls::deposit_bankroll(amount)).await
+	}
+
+	/// Redeems `shares` bankroll shares of `signer` for their part of the pot.
+	pub async fn withdraw_bankroll(
+		&self,
+		signer: &sr25519::Pair,
+		shares: Balance,
+	) -> Result<Included> {
+		self.submit(signer, calls::withdraw_bankroll(shares)).await
+	}
+
+	/// Pauses the play of `signer` for `duration` blocks.
+	pub async fn cool_off(
+		&self,
+		signer: &sr25519::Pair,
+		duration: BlockNumber,
+	) -> Result<Included> {
+		self.submit(signer, calls::cool_off(duration)).await
+	}
+
+	/// Excludes `signer` from play for at least `duration` blocks.
+	pub async fn self_exclude(
+		&self,
+		signer: &sr25519::Pair,
+		duration: BlockNumber,
+	) -> Result<Included> {
+		self.submit(signer, calls::self_exclude(duration)).await
+	}
+
+	/// Lifts the self-exclusion of `signer` once its term is over.
+	pub async fn lift_self_exclusion(&self, signer: &sr25519::Pair) -> Result<Included> {
+		self.submit(signer, calls::lift_self_exclusion()).await
+	}
+
+	/// Sets the daily and weekly loss limits of `signer`.
+	pub async fn set_loss_limits(
+		&self,
+		signer: &sr25519::Pair,
+		daily: Option<Balance>,
+		weekly: Option<Balance>,
+	) -> Result<Included> {
+		self.submit(signer, calls::set_loss_limits(daily, weekly)).await
+	}
+
+	/// Stakes `stake` of `signer` on the side its coin lands on when the bet is settled.
+	pub async fn place_bet(
+		&self,
+		signer: &sr25519::Pair,
+		side: CoinSide,
+		stake: Balance,
+	) -> Result<Included> {
+		self.submit(signer, calls::place_bet(side, stake)).await
+	}
+
+	/// Returns the coin of `account` at block `at`, or at the best block.
+	pub async fn coin(&self, account: &AccountId, at: Option<Hash>) -> Result<Option<Coin>> {
+		let mut key = storage_prefix(b"TemplateModule", b"CoinStorage").to_vec();
+		key.extend(Blake2_128Concat::hash(&account.encode()));
+		self.storage(StorageKey(key), at).await
+	}
+
+	/// Returns the free balance of `account` at block `at`, or at the best block.
+	pub async fn free_balance(&self, account: &AccountId, at: Option<Hash>) -> Result<Balance> {
+		let mut key = storage_prefix(b"System", b"Account").to_vec();
+		key.extend(Blake2_128Concat::hash(&account.encode()));
+		let info: Option<
+			frame_system::AccountInfo<Nonce, <Runtime as frame_system::Config>::AccountData>,
+		> = self.storage(StorageKey(key), at).await?;
+		Ok(info.map_or(0, |info| info.data.free))
+	}
+
+	/// Returns the events of the block `at`.
+	pub async fn events(&self, at: Hash) -> Result<Vec<EventRecord<RuntimeEvent, Hash>>> {
+		let key = StorageKey(storage_prefix(b"System", b"Events").to_vec());
+		Ok(self.storage(key, Some(at)).await?.unwrap_or_default())
+	}
+
+	/// Reads and decodes a storage value.
+	async fn storage<T: Decode>(&self, key: StorageKey, at: Option<Hash>) -> Result<Option<T>> {
+		let data: Option<StorageData> = StateApiClient::<Hash>::storage(&self.rpc, key, at).await?;
+		Ok(data.map(|data| T::decode(&mut &data.0[..])).transpose()?)
+	}
+}
@@ -0,0 +1,73 @@
+//! Tests of the typed client and its calls, against an in-process development node.
+
+use coin_flipper_client::{Client, CoinEvent, CoinSide, Error};
+use coin_flipper_e2e::TestNode;
+use node_template_runtime::AccountId;
+use sp_keyring::Sr25519Keyring;
+
+async fn start_dev_node() -> (TestNode, Client) {
+	let node = TestNode::start().await;
+	let client = Client::connect(&node.ws_url()).await.expect("Dev node serves RPC; qed");
+	(node, client)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn create_and_flip_coin() {
+	let (_node, client) = start_dev_node().await;
+	let bob = Sr25519Keyring::Bob.pair();
+	let account = AccountId::from(Sr25519Keyring::Bob.public());
+	assert_eq!(client.coin(&account, None).await.unwrap(), None);
+
+	let included = client.create_coin(&bob).await.unwrap();
+	assert_eq!(included.events, vec![CoinEvent::CoinCreated { who: account.clone() }]);
+	let coin = client.coin(&account, Some(included.block_hash)).await.unwrap().unwrap();
+	assert_eq!(coin.side(), &CoinSide::Head);
+
+	let included = client.flip(&bob).await.unwrap();
+	assert_eq!(
+		included.events,
+		vec![CoinEvent::CoinFlipped { who: account.clone(), side: CoinSide::Tail }]
+	);
+	let coin = client.coin(&account, Some(included.block_hash)).await.unwrap().unwrap();
+	assert_eq!(coin.side(), &CoinSide::Tail);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn toss_event_matches_stored_side() {
+	let (_node, client) = start_dev_node().await;
+	let alice = Sr25519Keyring::Alice.pair();
+	let account = AccountId::from(Sr25519Keyring::Alice.public());
+
+	client.create_coin(&alice).await.unwrap();
+	let included = client.toss(&alice).await.unwrap();
+
+	let coin = client.coin(&account, Some(included.block_hash)).await.unwrap().unwrap();
+	assert_eq!(
+		included.events,
+		vec![CoinEvent::CoinTossed { who: account, side: coin.side().clone(), nonce: 0 }]
+	);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn flip_without_coin_fails() {
+	let (_node, client) = start_dev_node().await;
+	let bob = Sr25519Keyring::Bob.pair();
+
+	assert!(matches!(client.flip(&bob).await, Err(Error::Dispatch(_))));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn operator_flips_for_owner() {
+	let (_node, client) = start_dev_node().await;
+	let alice = Sr25519Keyring::Alice.pair();
+	let owner = AccountId::from(Sr25519Keyring::Alice.public());
+	let bob = Sr25519Keyring::Bob.pair();
+	let operator = AccountId::from(Sr25519Keyring::Bob.public());
+
+	client.create_coin(&alice).await.unwrap();
+	assert!(matches!(client.flip_for(&bob, owner.clone()).await, Err(Error::Dispatch(_))));
+
+	client.approve_operator(&alice, operator, None).await.unwrap();
+	let included = client.flip_for(&bob, owner.clone()).await.unwrap();
+	assert_eq!(included.events, vec![CoinEvent::CoinFlipped { who: owner, side: CoinSide::Tail }]);
+}
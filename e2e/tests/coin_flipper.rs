@@ -0,0 +1,174 @@
+//! End-to-end tests of the coin-flipper runtime wiring, against an in-process development node.
+
+use coin_flipper_client::{Client, CoinEvent, CoinSide};
+use coin_flipper_e2e::{wait_for, TestNode, WAIT_TIMEOUT};
+use jsonrpsee::{
+	core::client::{ClientT, Subscription, SubscriptionClientT},
+	rpc_params,
+};
+use node_template::{
+	chain_spec::{self, coin_newcomer_seed, coin_owner_seed, get_account_id_from_seed},
+	coin_index::IndexedEvent,
+	rpc::coin_flipper::{CoinEventRecord, CoinOutcome, OutcomeStatus},
+};
+use node_template_runtime::{
+	pallet_template::{self, CoinStorage},
+	AccountId, Runtime, TemplateModule,
+};
+use sp_core::{sr25519, Get};
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::BuildStorage;
+
+async fn start_dev_node() -> (TestNode, Client) {
+	let node = TestNode::start().await;
+	let client = Client::connect(&node.ws_url()).await.expect("Dev node serves RPC; qed");
+	(node, client)
+}
+
+#[test]
+fn benchmark_genesis_has_coin_owners() {
+	let storage = chain_spec::benchmark_config().unwrap().build_storage().unwrap();
+	let owner = get_account_id_from_seed::<sr25519::Public>(&coin_owner_seed(0));
+	let newcomer = get_account_id_from_seed::<sr25519::Public>(&coin_newcomer_seed(0));
+
+	assert!(storage.top.contains_key(&CoinStorage::<Runtime>::hashed_key_for(&owner)));
+	assert!(!storage.top.contains_key(&CoinStorage::<Runtime>::hashed_key_for(&newcomer)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dev_genesis_has_no_coins() {
+	let (_node, client) = start_dev_node().await;
+	let owner = get_account_id_from_seed::<sr25519::Public>(&coin_owner_seed(0));
+
+	assert_eq!(client.coin(&owner, None).await.unwrap(), None);
+	let bob = AccountId::from(Sr25519Keyring::Bob.public());
+	assert_eq!(client.coin(&bob, None).await.unwrap(), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pot_pays_for_tosses_of_existing_coins() {
+	let (_node, client) = start_dev_node().await;
+	let bob = Sr25519Keyring::Bob.pair();
+	let account = AccountId::from(Sr25519Keyring::Bob.public());
+	let pot = TemplateModule::account_id();
+
+	// Creating the coin is paid by its owner.
+	let before = client.free_balance(&account, None).await.unwrap();
+	let pot_genesis = client.free_balance(&pot, None).await.unwrap();
+	let created = client.create_coin(&bob).await.unwrap();
+	let after_create = client.free_balance(&account, Some(created.block_hash)).await.unwrap();
+	assert!(after_create < before);
+	let pot_before = client.free_balance(&pot, Some(created.block_hash)).await.unwrap();
+	assert!(pot_before >= pot_genesis);
+
+	let tossed = client.toss(&bob).await.unwrap();
+	assert!(matches!(tossed.events.as_slice(), [CoinEvent::CoinTossed { .. }]));
+	assert_eq!(client.free_balance(&account, Some(tossed.block_hash)).await.unwrap(), after_create);
+	assert!(client.free_balance(&pot, Some(tossed.block_hash)).await.unwrap() < pot_before);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn account_events_rpc_returns_coin_history() {
+	let (node, client) = start_dev_node().await;
+	let bob = Sr25519Keyring::Bob.pair();
+	let account = AccountId::from(Sr25519Keyring::Bob.public());
+
+	let created = client.create_coin(&bob).await.unwrap();
+	let flipped = client.flip(&bob).await.unwrap();
+
+	let records: Vec<CoinEventRecord> = node
+		.rpc()
+		.await
+		.request("coinflipper_accountEvents", rpc_params![account.clone(), 1, None::<u32>])
+		.await
+		.unwrap();
+	let events: Vec<_> = records
+		.iter()
+		.map(|record| (record.event.as_str(), record.block_hash, record.side.as_deref()))
+		.collect();
+	assert_eq!(
+		events,
+		vec![
+			("CoinCreated", created.block_hash, None),
+			("CoinFlipped", flipped.block_hash, Some("Tail")),
+		]
+	);
+	assert!(records.iter().all(|record| record.account == account));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn coin_index_serves_finalized_events() {
+	let dir = tempfile::tempdir().unwrap();
+	let node = TestNode::start_with(Some(dir.path().join("coins.sqlite"))).await;
+	let client = Client::connect(&node.ws_url()).await.unwrap();
+	let rpc = node.rpc().await;
+	let alice = Sr25519Keyring::Alice.pair();
+	let account = AccountId::from(Sr25519Keyring::Alice.public());
+
+	let created = client.create_coin(&alice).await.unwrap();
+
+	let indexed: Vec<IndexedEvent> = wait_for("the coin creation to be indexed", || async {
+		let events: Vec<IndexedEvent> = rpc
+			.request("coinflipper_indexedEvents", rpc_params![account.clone(), None::<()>])
+			.await
+			.unwrap();
+		(!events.is_empty()).then_some(events)
+	})
+	.await;
+	assert_eq!(indexed.len(), 1);
+	assert_eq!(indexed[0].record.event, "CoinCreated");
+	assert_eq!(indexed[0].record.block_hash, created.block_hash);
+	assert!(indexed[0].timestamp > 0);
+}
+
+// Returns the next outcome of the subscription whose block became best
+async fn next_best_outcome(outcomes: &mut Subscription<CoinOutcome>) -> CoinOutcome {
+	loop {
+		let outcome = tokio::time::timeout(WAIT_TIMEOUT, outcomes.next())
+			.await
+			.expect("Outcome streamed in time; qed")
+			.expect("Subscription open; qed")
+			.unwrap();
+		if outcome.status == OutcomeStatus::Best {
+			return outcome
+		}
+	}
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn outcomes_carry_the_payout_of_settled_bets() {
+	let (node, client) = start_dev_node().await;
+	let rpc = node.rpc().await;
+	let alice = Sr25519Keyring::Alice.pair();
+	let account = AccountId::from(Sr25519Keyring::Alice.public());
+	client.create_coin(&alice).await.unwrap();
+
+	let mut outcomes: Subscription<CoinOutcome> = rpc
+		.subscribe(
+			"coinflipper_subscribeOutcomes",
+			rpc_params![account.clone()],
+			"coinflipper_unsubscribeOutcomes",
+		)
+		.await
+		.unwrap();
+
+	// The account's own toss settles no bet
+	let tossed = client.toss(&alice).await.unwrap();
+	let outcome = next_best_outcome(&mut outcomes).await;
+	assert_eq!(outcome.record.block_hash, tossed.block_hash);
+	assert_eq!(outcome.payout, None);
+
+	// The pallet's toss settling the bet carries its payout, or zero if the bet was lost
+	let placed = client.place_bet(&alice, CoinSide::Head, 1_000_000_000_000).await.unwrap();
+	let Some(CoinEvent::BetPlaced { payout, settle_at, .. }) = placed.events.last().cloned() else {
+		panic!("Expected BetPlaced, got {:?}", placed.events)
+	};
+	let delay: u32 = <Runtime as pallet_template::Config>::BetSettlementDelay::get();
+	node.seal_blocks(delay).await;
+	let outcome = next_best_outcome(&mut outcomes).await;
+	assert_eq!(outcome.mode, "toss");
+	assert_eq!(outcome.record.block_number, settle_at);
+	assert_eq!(outcome.record.extrinsic_index, None);
+	let expected = if outcome.record.side.as_deref() == Some("Head") { payout } else { 0 };
+	assert_eq!(outcome.payout, Some(expected));
+}
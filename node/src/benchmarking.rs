@@ -2,10 +2,13 @@
 //!
 //! Should only be used for benchmarking as it may break in other contexts.
 
-use crate::service::FullClient;
+use crate::{
+	chain_spec::{coin_newcomer_seed, coin_owner_seed, COIN_BENCHMARK_ACCOUNTS},
+	service::FullClient,
+};
 
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{AccountId, Balance, BalancesCall, SystemCall, TemplateModuleCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
 use sp_core::{Encode, Pair};
@@ -92,6 +95,69 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 	}
 }
 
+/// The coin-flipper calls that [`CoinCallBuilder`] can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoinCall {
+	/// `create_coin`, sent by funded accounts that do not own a coin yet.
+	Create,
+	/// `do_flip`, sent by accounts that own a coin at genesis.
+	Flip,
+	/// `do_toss`, sent by accounts that own a coin at genesis.
+	Toss,
+}
+
+/// Generates `TemplateModule` coin extrinsics for the benchmarks.
+///
+/// Every extrinsic is signed by a different pre-created account of the benchmark chain, so
+/// that none of them fails because the coin already exists or the sender is rate limited. At most
+/// [`COIN_BENCHMARK_ACCOUNTS`] extrinsics can be built, use `--max-ext-per-block` accordingly.
+///
+/// Note: Should only be used for benchmarking.
+pub struct CoinCallBuilder {
+	client: Arc<FullClient>,
+	call: CoinCall,
+}
+
+impl CoinCallBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>, call: CoinCall) -> Self {
+		Self { client, call }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for CoinCallBuilder {
+	fn pallet(&self) -> &str {
+		"template_module"
+	}
+
+	fn extrinsic(&self) -> &str {
+		match self.call {
+			CoinCall::Create => "create_coin",
+			CoinCall::Flip => "do_flip",
+			CoinCall::Toss => "do_toss",
+		}
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		if nonce >= COIN_BENCHMARK_ACCOUNTS {
+			return Err("Not enough pre-created coin accounts, lower --max-ext-per-block")
+		}
+
+		let (seed, call) = match self.call {
+			CoinCall::Create => (coin_newcomer_seed(nonce), TemplateModuleCall::create_coin {}),
+			CoinCall::Flip => (coin_owner_seed(nonce), TemplateModuleCall::do_flip {}),
+			CoinCall::Toss => (coin_owner_seed(nonce), TemplateModuleCall::do_toss {}),
+		};
+		let acc = sp_core::sr25519::Pair::from_string(&format!("//{}", seed), None)
+			.map_err(|_| "Invalid coin account seed")?;
+		// Each account only sends this one extrinsic.
+		let extrinsic: OpaqueExtrinsic =
+			create_benchmark_extrinsic(self.client.as_ref(), acc, call.into(), 0).into();
+
+		Ok(extrinsic)
+	}
+}
+
 /// Create a transaction using the given `call`.
 ///
 /// Note: Should only be used for benchmarking.
@@ -121,6 +187,7 @@ pub fn create_benchmark_extrinsic(
 		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
 		frame_system::CheckWeight::<runtime::Runtime>::new(),
 		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		runtime::pallet_template::CheckTossRate::<runtime::Runtime>::new(),
 	);
 
 	let raw_payload = runtime::SignedPayload::from_raw(
@@ -135,6 +202,7 @@ pub fn create_benchmark_extrinsic(
 			(),
 			(),
 			(),
+			(),
 		),
 	);
 	let signature = raw_payload.using_encoded(|e| sender.sign(e));
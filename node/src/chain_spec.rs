@@ -1,6 +1,7 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GrandpaConfig, RuntimeGenesisConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, CouncilMembershipConfig, GrandpaConfig,
+	RuntimeGenesisConfig, Signature, SystemConfig, TemplateModule, TemplateModuleConfig,
+	WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -31,6 +32,20 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
+/// Number of accounts of each kind pre-created in the benchmark chain for benchmarking the
+/// coin-flipper calls.
+pub const COIN_BENCHMARK_ACCOUNTS: u32 = 1000;
+
+/// Seed of the `index`-th benchmarking account that owns a coin at genesis.
+pub fn coin_owner_seed(index: u32) -> String {
+	format!("CoinBench//Owner//{}", index)
+}
+
+/// Seed of the `index`-th benchmarking account that is funded but has no coin at genesis.
+pub fn coin_newcomer_seed(index: u32) -> String {
+	format!("CoinBench//Newcomer//{}", index)
+}
+
 /// Generate an Aura authority key.
 pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
@@ -50,8 +65,11 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				wasm_binary,
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice")],
-				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// Council members
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
 				// Pre-funded accounts
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -59,6 +77,66 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Accounts owning a coin
+				vec![],
+				true,
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		None,
+		// Properties
+		None,
+		// Extensions
+		None,
+	))
+}
+
+/// The development chain, plus [`COIN_BENCHMARK_ACCOUNTS`] funded accounts that own a coin and
+/// as many that do not, used by `benchmark extrinsic`.
+pub fn benchmark_config() -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+
+	let coin_owners: Vec<AccountId> = (0..COIN_BENCHMARK_ACCOUNTS)
+		.map(|i| get_account_id_from_seed::<sr25519::Public>(&coin_owner_seed(i)))
+		.collect();
+	let coin_newcomers: Vec<AccountId> = (0..COIN_BENCHMARK_ACCOUNTS)
+		.map(|i| get_account_id_from_seed::<sr25519::Public>(&coin_newcomer_seed(i)))
+		.collect();
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"Benchmark",
+		// ID
+		"benchmark",
+		ChainType::Development,
+		move || {
+			testnet_genesis(
+				wasm_binary,
+				// Initial PoA authorities
+				vec![authority_keys_from_seed("Alice")],
+				// Council members
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
+				// Pre-funded accounts
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+				]
+				.into_iter()
+				.chain(coin_owners.iter().cloned())
+				.chain(coin_newcomers.iter().cloned())
+				.collect(),
+				// Accounts owning a coin, used by `benchmark extrinsic`
+				coin_owners.clone(),
 				true,
 			)
 		},
@@ -90,8 +168,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				wasm_binary,
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
-				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// Council members
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
 				// Pre-funded accounts
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -107,6 +189,8 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Accounts owning a coin
+				vec![],
 				true,
 			)
 		},
@@ -128,8 +212,9 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 fn testnet_genesis(
 	wasm_binary: &[u8],
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
+	council_members: Vec<AccountId>,
 	endowed_accounts: Vec<AccountId>,
+	coin_owners: Vec<AccountId>,
 	_enable_println: bool,
 ) -> RuntimeGenesisConfig {
 	RuntimeGenesisConfig {
@@ -140,7 +225,13 @@ fn testnet_genesis(
 		},
 		balances: BalancesConfig {
 			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			// The coin-flipper pot is endowed too, so that it can sponsor tosses.
+			balances: endowed_accounts
+				.iter()
+				.cloned()
+				.chain(std::iter::once(TemplateModule::account_id()))
+				.map(|k| (k, 1 << 60))
+				.collect(),
 		},
 		aura: AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -149,10 +240,16 @@ fn testnet_genesis(
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
 			..Default::default()
 		},
-		sudo: SudoConfig {
-			// Assign network admin rights.
-			key: Some(root_key),
-		},
 		transaction_payment: Default::default(),
+		// The council is initialized by its membership pallet.
+		council: Default::default(),
+		treasury: Default::default(),
+		council_membership: CouncilMembershipConfig {
+			members: council_members
+				.try_into()
+				.expect("council members fit in CouncilMaxMembers; qed"),
+			..Default::default()
+		},
+		template_module: TemplateModuleConfig { coins: coin_owners },
 	}
 }
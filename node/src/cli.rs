@@ -1,4 +1,5 @@
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +8,10 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Index the coin-flipper events of finalized blocks into a SQLite database at this path.
+	#[arg(long, value_name = "PATH")]
+	pub coin_index: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -39,7 +44,7 @@ pub enum Subcommand {
 
 	/// Sub-commands concerned with benchmarking.
 	#[command(subcommand)]
-	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+	Benchmark(BenchmarkSubcommand),
 
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
@@ -51,4 +56,20 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Inspect coin-flipper state from the local database.
+	#[command(subcommand)]
+	Coin(crate::coin::CoinCmd),
+}
+
+/// Benchmarking sub-commands: the stock FRAME ones plus the coin-flipper specific ones.
+#[derive(Debug, clap::Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum BenchmarkSubcommand {
+	/// Benchmark the quality of the coin-flipper randomness source.
+	Randomness(crate::randomness::RandomnessCmd),
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	Frame(frame_benchmarking_cli::BenchmarkCmd),
 }
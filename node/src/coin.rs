@@ -0,0 +1,318 @@
+//! `coin` sub-commands for inspecting coin-flipper state straight from the local database.
+//!
+//! These commands only build the partial service components, so no networking is started and
+//! the node does not need to be running. They are meant for operators who want to answer
+//! questions like "what was Alice's coin at block N" without an RPC endpoint.
+
+use crate::{
+	rpc::coin_flipper::{coin_events_at, CoinEventRecord},
+	service::FullClient,
+};
+
+use codec::Decode;
+use frame_support::{storage::StoragePrefixedMap, Blake2_128Concat, ReversibleStorageHasher};
+use node_template_runtime::{
+	opaque::Block,
+	pallet_template::{Coin, CoinSide, CoinStorage},
+	AccountId, BlockNumber, Runtime,
+};
+use sc_cli::{
+	BlockNumberOrHash, CliConfiguration, DatabaseParams, GenericNumber, PruningParams, Result,
+	SharedParams,
+};
+use sc_client_api::StorageProvider;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::PathBuf,
+};
+
+type Hash = <Block as BlockT>::Hash;
+
+/// Inspect coin-flipper storage from the local database.
+#[derive(Debug, clap::Subcommand)]
+pub enum CoinCmd {
+	/// Show the coin owned by an account.
+	Show(ShowCmd),
+
+	/// Print aggregate statistics over all coins.
+	Stats(StatsCmd),
+
+	/// Export every coin as JSON lines.
+	Export(ExportCmd),
+
+	/// Export the coin event history of a block range as CSV or JSON lines.
+	History(HistoryCmd),
+}
+
+/// Parameters shared by all `coin` sub-commands.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct CoinParams {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The `coin show` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ShowCmd {
+	/// The account to look up, as SS58 or hex.
+	#[arg(value_name = "ACCOUNT")]
+	pub account: AccountId,
+
+	/// Block hash or number to read the state at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub params: CoinParams,
+}
+
+/// The `coin stats` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct StatsCmd {
+	/// Block hash or number to read the state at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub params: CoinParams,
+}
+
+/// The `coin export` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportCmd {
+	/// Block hash or number to read the state at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub params: CoinParams,
+}
+
+/// The `coin history` command.
+///
+/// Events are read from the state of each block, not from block bodies, so the state of the whole
+/// range must still be in the database. Nodes that should export their full history have to run
+/// with `--state-pruning archive`.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct HistoryCmd {
+	/// Specify starting block number.
+	#[arg(long, value_name = "BLOCK", default_value = "1")]
+	pub from: GenericNumber,
+
+	/// Specify last block number. Defaults to the best block.
+	#[arg(long, value_name = "BLOCK")]
+	pub to: Option<GenericNumber>,
+
+	/// Output format of the exported events.
+	#[arg(long, value_enum, default_value_t = HistoryFormat::Csv)]
+	pub format: HistoryFormat,
+
+	/// Output file name or stdout if unspecified.
+	#[arg(long, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub params: CoinParams,
+}
+
+/// Output formats supported by `coin history`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HistoryFormat {
+	/// Comma separated values with a header line.
+	Csv,
+	/// One JSON object per line.
+	Json,
+}
+
+impl CoinCmd {
+	fn params(&self) -> &CoinParams {
+		match self {
+			CoinCmd::Show(cmd) => &cmd.params,
+			CoinCmd::Stats(cmd) => &cmd.params,
+			CoinCmd::Export(cmd) => &cmd.params,
+			CoinCmd::History(cmd) => &cmd.params,
+		}
+	}
+
+	/// Run the selected command against the given client.
+	pub fn run(&self, client: &FullClient) -> Result<()> {
+		match self {
+			CoinCmd::Show(cmd) => cmd.run(client),
+			CoinCmd::Stats(cmd) => cmd.run(client),
+			CoinCmd::Export(cmd) => cmd.run(client),
+			CoinCmd::History(cmd) => cmd.run(client),
+		}
+	}
+}
+
+/// Resolve `--at` to a block hash, falling back to the best block.
+fn block_hash(client: &FullClient, at: &Option<BlockNumberOrHash>) -> Result<Hash> {
+	match at {
+		Some(at) => Ok(client.expect_block_hash_from_id(&at.parse::<Block>()?)?),
+		None => Ok(client.info().best_hash),
+	}
+}
+
+impl ShowCmd {
+	fn run(&self, client: &FullClient) -> Result<()> {
+		let hash = block_hash(client, &self.at)?;
+		let key = StorageKey(CoinStorage::<Runtime>::hashed_key_for(&self.account));
+		let coin = client
+			.storage(hash, &key)?
+			.map(|data| Coin::decode(&mut &data.0[..]))
+			.transpose()
+			.map_err(|e| format!("Failed to decode coin: {}", e))?;
+
+		match coin {
+			Some(coin) => println!("{}: {:?} (at {:?})", self.account, coin.side(), hash),
+			None => println!("{}: no coin (at {:?})", self.account, hash),
+		}
+		Ok(())
+	}
+}
+
+impl StatsCmd {
+	fn run(&self, client: &FullClient) -> Result<()> {
+		let hash = block_hash(client, &self.at)?;
+		let coins = coins_at(client, hash)?;
+		let heads = coins.iter().filter(|(_, coin)| *coin.side() == CoinSide::Head).count();
+		let tails = coins.len() - heads;
+
+		println!("block: {:?}", hash);
+		println!("coins: {}", coins.len());
+		println!("heads: {}", heads);
+		println!("tails: {}", tails);
+		Ok(())
+	}
+}
+
+impl ExportCmd {
+	fn run(&self, client: &FullClient) -> Result<()> {
+		let hash = block_hash(client, &self.at)?;
+		for (account, coin) in coins_at(client, hash)? {
+			let line = serde_json::json!({
+				"block": format!("{:?}", hash),
+				"account": account.to_string(),
+				"side": format!("{:?}", coin.side()),
+			});
+			println!("{}", line);
+		}
+		Ok(())
+	}
+}
+
+impl HistoryCmd {
+	fn run(&self, client: &FullClient) -> Result<()> {
+		let from: BlockNumber = self.from.parse()?;
+		let to: BlockNumber = match &self.to {
+			Some(to) => to.parse()?,
+			None => client.info().best_number,
+		};
+
+		let mut output: Box<dyn Write> = match &self.output {
+			Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+			None => Box::new(io::stdout()),
+		};
+
+		if self.format == HistoryFormat::Csv {
+			writeln!(output, "block,extrinsic,event,account,side")?;
+		}
+
+		for number in from..=to {
+			let hash = client
+				.hash(number)?
+				.ok_or_else(|| format!("Block {} not found in the database", number))?;
+
+			let events = coin_events_at(client, number, hash).map_err(|e| {
+				format!(
+					"Failed to read the events of block {}: {}. Its state may have been pruned, \
+					 `coin history` needs a node run with `--state-pruning archive`",
+					number, e
+				)
+			})?;
+			for (_, record) in events {
+				if !matches!(record.event.as_str(), "CoinCreated" | "CoinFlipped" | "CoinTossed") {
+					continue
+				}
+				let CoinEventRecord {
+					extrinsic_index: extrinsic, event: name, account, side, ..
+				} = record;
+				let side = side.unwrap_or_default();
+
+				match self.format {
+					HistoryFormat::Csv => writeln!(
+						output,
+						"{},{},{},{},{}",
+						number,
+						extrinsic.map(|i| i.to_string()).unwrap_or_default(),
+						name,
+						account,
+						side
+					)?,
+					HistoryFormat::Json => writeln!(
+						output,
+						"{}",
+						serde_json::json!({
+							"block": number,
+							"extrinsic": extrinsic,
+							"event": name,
+							"account": account.to_string(),
+							"side": side,
+						})
+					)?,
+				}
+			}
+		}
+
+		output.flush()?;
+		Ok(())
+	}
+}
+
+/// Read every `CoinStorage` entry at the given block.
+pub fn coins_at(client: &FullClient, hash: Hash) -> Result<Vec<(AccountId, Coin)>> {
+	let prefix = StorageKey(CoinStorage::<Runtime>::final_prefix().to_vec());
+
+	client
+		.storage_pairs(hash, Some(&prefix), None)?
+		.map(|(key, value)| {
+			let mut raw_account = Blake2_128Concat::reverse(&key.0[prefix.0.len()..]);
+			let account = AccountId::decode(&mut raw_account)
+				.map_err(|e| format!("Failed to decode account: {}", e))?;
+			let coin = Coin::decode(&mut &value.0[..])
+				.map_err(|e| format!("Failed to decode coin: {}", e))?;
+			Ok((account, coin))
+		})
+		.collect()
+}
+
+impl CliConfiguration for CoinCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.params().shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.params().pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.params().database_params)
+	}
+}
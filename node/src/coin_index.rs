@@ -0,0 +1,253 @@
+//! Optional SQLite index of coin-flipper events, enabled with `--coin-index <path>`.
+//!
+//! Only finalized blocks are indexed, so the index never sees a block that a reorg of the best
+//! chain could drop. The hash of every indexed block is kept as well: whenever the indexed chain
+//! no longer matches the finalized one, e.g. after `revert`, the index is rolled back to the last
+//! block both agree on and rebuilt from there.
+
+use std::{
+	path::Path,
+	sync::{Arc, Mutex},
+};
+
+use frame_support::storage::storage_prefix;
+use futures::StreamExt;
+use node_template_runtime::{opaque::Block, AccountId, BlockNumber, Hash};
+use rusqlite::{params, Connection, OptionalExtension};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, H256};
+
+use crate::rpc::coin_flipper::{coin_events_at, read_storage, CoinEventRecord};
+
+/// The maximum number of events a single query returns.
+pub const MAX_QUERY_RESULTS: u32 = 1_000;
+
+const SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS blocks (
+		number INTEGER PRIMARY KEY,
+		hash BLOB NOT NULL,
+		timestamp INTEGER NOT NULL
+	);
+	CREATE TABLE IF NOT EXISTS events (
+		block_number INTEGER NOT NULL REFERENCES blocks(number) ON DELETE CASCADE,
+		event_index INTEGER NOT NULL,
+		extrinsic_index INTEGER,
+		event TEXT NOT NULL,
+		account BLOB NOT NULL,
+		side TEXT,
+		nonce INTEGER,
+		PRIMARY KEY (block_number, event_index)
+	);
+	CREATE INDEX IF NOT EXISTS events_by_account ON events (account, block_number);
+";
+
+/// A coin-flipper event read back from the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedEvent {
+	/// Timestamp of the block that contains the event, in milliseconds since the Unix epoch.
+	pub timestamp: u64,
+	/// The event and where it was emitted.
+	#[serde(flatten)]
+	pub record: CoinEventRecord,
+}
+
+/// Which events of an account a query returns.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+	/// Only events in blocks with this timestamp or later, in milliseconds.
+	pub from_time: Option<u64>,
+	/// Only events in blocks with this timestamp or earlier, in milliseconds.
+	pub to_time: Option<u64>,
+	/// Only flips and tosses that landed on this side, `Head` or `Tail`.
+	pub side: Option<String>,
+	/// The maximum number of events to return, at most [`MAX_QUERY_RESULTS`].
+	pub limit: Option<u32>,
+}
+
+/// A SQLite database of the coin-flipper events in finalized blocks.
+pub struct CoinIndex {
+	connection: Mutex<Connection>,
+}
+
+impl CoinIndex {
+	/// Opens the index at `path`, creating it if needed.
+	pub fn open(path: &Path) -> rusqlite::Result<Self> {
+		let connection = Connection::open(path)?;
+		connection.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")?;
+		connection.execute_batch(SCHEMA)?;
+		Ok(Self { connection: Mutex::new(connection) })
+	}
+
+	/// Returns the number and hash of the last indexed block.
+	pub fn last_indexed(&self) -> rusqlite::Result<Option<(BlockNumber, Hash)>> {
+		let connection = self.connection.lock().expect("Index lock poisoned; qed");
+		connection
+			.query_row("SELECT number, hash FROM blocks ORDER BY number DESC LIMIT 1", [], |row| {
+				Ok((row.get(0)?, H256::from_slice(&row.get::<_, Vec<u8>>(1)?)))
+			})
+			.optional()
+	}
+
+	/// Stores the events of a block, replacing whatever was stored for its number.
+	pub fn index_block(
+		&self,
+		number: BlockNumber,
+		hash: Hash,
+		timestamp: u64,
+		events: &[(u32, CoinEventRecord)],
+	) -> rusqlite::Result<()> {
+		let mut connection = self.connection.lock().expect("Index lock poisoned; qed");
+		let transaction = connection.transaction()?;
+		transaction.execute("DELETE FROM blocks WHERE number = ?1", params![number])?;
+		transaction.execute(
+			"INSERT INTO blocks (number, hash, timestamp) VALUES (?1, ?2, ?3)",
+			params![number, hash.as_bytes(), timestamp],
+		)?;
+		for (index, record) in events {
+			transaction.execute(
+				"INSERT INTO events
+					(block_number, event_index, extrinsic_index, event, account, side, nonce)
+					VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+				params![
+					number,
+					index,
+					record.extrinsic_index,
+					record.event,
+					AsRef::<[u8]>::as_ref(&record.account),
+					record.side,
+					record.nonce,
+				],
+			)?;
+		}
+		transaction.commit()
+	}
+
+	/// Drops block `number` and every block above it, together with their events.
+	pub fn revert_from(&self, number: BlockNumber) -> rusqlite::Result<()> {
+		let connection = self.connection.lock().expect("Index lock poisoned; qed");
+		connection
+			.execute("DELETE FROM blocks WHERE number >= ?1", params![number])
+			.map(|_| ())
+	}
+
+	/// Returns the events of `account` that match `filter`, oldest first.
+	pub fn events(
+		&self,
+		account: &AccountId,
+		filter: &EventFilter,
+	) -> rusqlite::Result<Vec<IndexedEvent>> {
+		let connection = self.connection.lock().expect("Index lock poisoned; qed");
+		let mut statement = connection.prepare_cached(
+			"SELECT blocks.number, blocks.hash, blocks.timestamp,
+					events.extrinsic_index, events.event, events.side, events.nonce
+				FROM events JOIN blocks ON blocks.number = events.block_number
+				WHERE events.account = ?1
+					AND (?2 IS NULL OR blocks.timestamp >= ?2)
+					AND (?3 IS NULL OR blocks.timestamp <= ?3)
+					AND (?4 IS NULL OR events.side = ?4)
+				ORDER BY events.block_number, events.event_index
+				LIMIT ?5",
+		)?;
+		let limit = filter.limit.unwrap_or(MAX_QUERY_RESULTS).min(MAX_QUERY_RESULTS);
+		let rows = statement.query_map(
+			params![
+				AsRef::<[u8]>::as_ref(account),
+				filter.from_time,
+				filter.to_time,
+				filter.side,
+				limit
+			],
+			|row| {
+				Ok(IndexedEvent {
+					timestamp: row.get(2)?,
+					record: CoinEventRecord {
+						block_number: row.get(0)?,
+						block_hash: H256::from_slice(&row.get::<_, Vec<u8>>(1)?),
+						extrinsic_index: row.get(3)?,
+						event: row.get(4)?,
+						account: account.clone(),
+						side: row.get(5)?,
+						nonce: row.get(6)?,
+					},
+				})
+			},
+		)?;
+		rows.collect()
+	}
+}
+
+/// Keeps the index in sync with the finalized chain until the node shuts down.
+///
+/// Returns on the first error, which takes the node down with it when spawned as an essential
+/// task, rather than leaving a silently stale index behind. Blocks whose state was pruned are
+/// not an error, they are skipped.
+pub async fn run<C, B>(client: Arc<C>, index: Arc<CoinIndex>)
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B> + HeaderBackend<Block> + BlockchainEvents<Block>,
+{
+	let mut finality = client.finality_notification_stream();
+	loop {
+		if let Err(e) = catch_up(&*client, &index) {
+			log::error!(target: "coin-index", "Failed to index finalized blocks: {}", e);
+			return
+		}
+		if finality.next().await.is_none() {
+			return
+		}
+	}
+}
+
+/// Rolls the index back to the finalized chain, then indexes every finalized block it misses.
+///
+/// Blocks whose state was pruned are skipped with a warning, so a pruned node indexes the blocks
+/// it still has the state of instead of failing on the first one it lacks.
+fn catch_up<C, B>(client: &C, index: &CoinIndex) -> Result<(), String>
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B> + HeaderBackend<Block>,
+{
+	let finalized = client.info().finalized_number;
+	let mut next = 0;
+	while let Some((number, hash)) = index.last_indexed().map_err(|e| e.to_string())? {
+		if number <= finalized && client.hash(number).map_err(|e| e.to_string())? == Some(hash) {
+			next = number + 1;
+			break
+		}
+		log::info!(target: "coin-index", "Block #{} is no longer finalized, reverting it", number);
+		index.revert_from(number).map_err(|e| e.to_string())?;
+	}
+
+	let mut pruned = None;
+	for number in next..=finalized {
+		let Some(hash) = client.hash(number).map_err(|e| e.to_string())? else { break };
+		// The state of pruned blocks can no longer be read, their events are gone with it.
+		let Ok(timestamp) = read_storage::<_, _, u64>(client, hash, &timestamp_key()) else {
+			pruned.get_or_insert((number, number)).1 = number;
+			continue
+		};
+		let events = coin_events_at(client, number, hash)?;
+		index
+			.index_block(number, hash, timestamp.unwrap_or_default(), &events)
+			.map_err(|e| e.to_string())?;
+	}
+	if let Some((first, last)) = pruned {
+		log::warn!(
+			target: "coin-index",
+			"State of blocks #{}..=#{} was pruned, their coin events are not indexed. \
+			 Run the node with --state-pruning archive to index the whole chain",
+			first,
+			last,
+		);
+	}
+	Ok(())
+}
+
+/// Storage key of the timestamp of a block.
+fn timestamp_key() -> StorageKey {
+	StorageKey(storage_prefix(b"Timestamp", b"Now").to_vec())
+}
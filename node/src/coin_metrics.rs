@@ -0,0 +1,189 @@
+//! Prometheus metrics of coin-flipper activity.
+//!
+//! The counters, the heads ratio and the pot balance are updated from the events and state of
+//! finalized blocks, so they lag the best block by the finality delay but never count a block that
+//! a reorg drops. Only the pending tosses, read from the transaction pool, follow the best chain.
+//! The metrics are meant for dashboards and alerts, e.g. on a skewed heads/tails ratio or a
+//! draining pot.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use frame_support::{storage::storage_prefix, Blake2_128Concat, StorageHasher};
+use futures::{future, stream, StreamExt};
+use node_template_runtime::{
+	opaque::Block, pallet_template, Hash, Nonce, Runtime, RuntimeCall, TemplateModule,
+	UncheckedExtrinsic,
+};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use substrate_prometheus_endpoint::{
+	register, Counter, CounterVec, Gauge, Opts, PrometheusError, Registry, F64, U64,
+};
+
+use crate::rpc::coin_flipper::{coin_events_at, read_storage, CoinEventRecord};
+
+/// The coin-flipper metrics exported by the node.
+#[derive(Clone)]
+pub struct CoinMetrics {
+	coins_created: Counter<U64>,
+	outcomes: CounterVec<U64>,
+	heads_ratio: Gauge<F64>,
+	pot_balance: Gauge<F64>,
+	pending_tosses: Gauge<U64>,
+}
+
+impl CoinMetrics {
+	/// Registers the metrics in `registry`.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			coins_created: register(
+				Counter::new(
+					"substrate_coinflipper_coins_created_total",
+					"Number of coins created in finalized blocks",
+				)?,
+				registry,
+			)?,
+			outcomes: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_coinflipper_outcomes_total",
+						"Number of flips and tosses in finalized blocks, by mode and side",
+					),
+					&["mode", "side"],
+				)?,
+				registry,
+			)?,
+			heads_ratio: register(
+				Gauge::new(
+					"substrate_coinflipper_heads_ratio",
+					"Fraction of finalized flips and tosses that landed on heads since the node started",
+				)?,
+				registry,
+			)?,
+			pot_balance: register(
+				Gauge::new(
+					"substrate_coinflipper_pot_balance",
+					"Free balance of the pallet pot at the last finalized block",
+				)?,
+				registry,
+			)?,
+			pending_tosses: register(
+				Gauge::new(
+					"substrate_coinflipper_pending_tosses",
+					"Number of flips and tosses ready in the transaction pool, at the best block",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+// What the metrics are updated on
+enum Update {
+	// A new best block was imported, the transaction pool changed with it
+	NewBest,
+	// Blocks were finalized
+	Finalized,
+}
+
+/// Updates `metrics` until the node shuts down.
+///
+/// The counters, the heads ratio and the pot balance follow the finalized chain, so every
+/// finalized block is counted once and blocks dropped by a reorg never are. The pending tosses
+/// are counted on every new best block, as the transaction pool follows the best chain.
+pub async fn run<C, B, P>(client: Arc<C>, pool: Arc<P>, metrics: CoinMetrics)
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	P: TransactionPool<Block = Block>,
+{
+	let new_best = client
+		.import_notification_stream()
+		.filter(|notification| future::ready(notification.is_new_best))
+		.map(|_| Update::NewBest);
+	let finalized = client.finality_notification_stream().map(|_| Update::Finalized);
+	let mut updates = stream::select(new_best, finalized);
+
+	// Blocks finalized before the node started are not counted
+	let mut counted = client.info().finalized_number;
+	while let Some(update) = updates.next().await {
+		match update {
+			Update::NewBest => metrics.pending_tosses.set(pending_tosses(&*pool)),
+			Update::Finalized => {
+				let info = client.info();
+				for number in counted + 1..=info.finalized_number {
+					let Ok(Some(hash)) = client.hash(number) else { break };
+					count_outcomes(&metrics, coin_events_at(&*client, number, hash));
+					counted = number;
+				}
+				metrics.pot_balance.set(pot_balance(&*client, info.finalized_hash));
+			},
+		}
+	}
+}
+
+// Counts the coins created and the outcomes among `events`, and updates the heads ratio
+fn count_outcomes(metrics: &CoinMetrics, events: Result<Vec<(u32, CoinEventRecord)>, String>) {
+	for (_, record) in events.unwrap_or_default() {
+		let mode = match record.event.as_str() {
+			"CoinCreated" => {
+				metrics.coins_created.inc();
+				continue
+			},
+			"CoinFlipped" => "flip",
+			"CoinTossed" => "toss",
+			_ => continue,
+		};
+		let side = record.side.as_deref().unwrap_or_default();
+		metrics.outcomes.with_label_values(&[mode, side]).inc();
+	}
+
+	let count = |side: &str| {
+		["flip", "toss"]
+			.iter()
+			.map(|mode| metrics.outcomes.with_label_values(&[mode, side]).get())
+			.sum::<u64>() as f64
+	};
+	let (heads, tails) = (count("Head"), count("Tail"));
+	if heads + tails > 0.0 {
+		metrics.heads_ratio.set(heads / (heads + tails));
+	}
+}
+
+// Returns the free balance of the pot at block `hash`
+fn pot_balance<C, B>(client: &C, hash: Hash) -> f64
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B>,
+{
+	let pot = TemplateModule::account_id();
+	let mut key = storage_prefix(b"System", b"Account").to_vec();
+	key.extend(Blake2_128Concat::hash(&pot.encode()));
+	let account: Option<
+		frame_system::AccountInfo<Nonce, <Runtime as frame_system::Config>::AccountData>,
+	> = read_storage(client, hash, &StorageKey(key)).unwrap_or_default();
+	account.map_or(0.0, |account| account.data.free as f64)
+}
+
+// Returns the number of flips and tosses ready in the transaction pool
+fn pending_tosses<P: TransactionPool<Block = Block>>(pool: &P) -> u64 {
+	pool.ready()
+		.filter(|tx| {
+			let Ok(xt) = UncheckedExtrinsic::decode(&mut &tx.data().encode()[..]) else {
+				return false
+			};
+			matches!(
+				xt.function,
+				RuntimeCall::TemplateModule(
+					pallet_template::Call::do_flip {} |
+						pallet_template::Call::do_toss {} |
+						pallet_template::Call::flip_for { .. } |
+						pallet_template::Call::toss_for { .. }
+				)
+			)
+		})
+		.count() as u64
+}
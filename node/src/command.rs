@@ -1,7 +1,9 @@
 use crate::{
-	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
+	benchmarking::{
+		inherent_benchmark_data, CoinCall, CoinCallBuilder, RemarkBuilder, TransferKeepAliveBuilder,
+	},
 	chain_spec,
-	cli::{Cli, Subcommand},
+	cli::{BenchmarkSubcommand, Cli, Subcommand},
 	service,
 };
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
@@ -41,6 +43,7 @@ impl SubstrateCli for Cli {
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
+			"benchmark" => Box::new(chain_spec::benchmark_config()?),
 			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
 			path =>
 				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
@@ -104,7 +107,11 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, backend, Some(aux_revert)), task_manager))
 			})
 		},
-		Some(Subcommand::Benchmark(cmd)) => {
+		Some(Subcommand::Benchmark(BenchmarkSubcommand::Randomness(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|_| cmd.run())
+		},
+		Some(Subcommand::Benchmark(BenchmarkSubcommand::Frame(cmd))) => {
 			let runner = cli.create_runner(cmd)?;
 
 			runner.sync_run(|config| {
@@ -154,7 +161,7 @@ pub fn run() -> sc_cli::Result<()> {
 					},
 					BenchmarkCmd::Extrinsic(cmd) => {
 						let PartialComponents { client, .. } = service::new_partial(&config)?;
-						// Register the *Remark* and *TKA* builders.
+						// Register the *Remark*, *TKA* and coin builders.
 						let ext_factory = ExtrinsicFactory(vec![
 							Box::new(RemarkBuilder::new(client.clone())),
 							Box::new(TransferKeepAliveBuilder::new(
@@ -162,6 +169,9 @@ pub fn run() -> sc_cli::Result<()> {
 								Sr25519Keyring::Alice.to_account_id(),
 								EXISTENTIAL_DEPOSIT,
 							)),
+							Box::new(CoinCallBuilder::new(client.clone(), CoinCall::Create)),
+							Box::new(CoinCallBuilder::new(client.clone(), CoinCall::Flip)),
+							Box::new(CoinCallBuilder::new(client.clone(), CoinCall::Toss)),
 						]);
 
 						cmd.run(client, inherent_benchmark_data()?, Vec::new(), &ext_factory)
@@ -202,10 +212,18 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run::<Block>(&config))
 		},
+		Some(Subcommand::Coin(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				cmd.run(&client)
+			})
+		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let coin_index = cli.coin_index.clone();
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, coin_index).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
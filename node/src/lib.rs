@@ -1,3 +1,5 @@
 pub mod chain_spec;
+pub mod coin_index;
+pub mod coin_metrics;
 pub mod rpc;
 pub mod service;
@@ -6,7 +6,11 @@ mod chain_spec;
 mod service;
 mod benchmarking;
 mod cli;
+mod coin;
+mod coin_index;
+mod coin_metrics;
 mod command;
+mod randomness;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
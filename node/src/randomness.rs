@@ -0,0 +1,224 @@
+//! The `benchmark randomness` command.
+//!
+//! Runs the runtime's configured `MyRandomness` source over many synthetic blocks inside a
+//! `TestExternalities` and reports how well the outcome of `do_toss` behaves as a fair coin. The
+//! parent hashes of the synthetic blocks are derived from `--seed`, so runs are reproducible and
+//! different randomness adapters can be compared on the same input.
+
+use codec::Encode;
+use frame_support::traits::OnInitialize;
+use node_template_runtime::{
+	pallet_template::CoinSide, AccountId, AllPalletsWithSystem, BlockNumber, BuildStorage, Runtime,
+	TemplateModule,
+};
+use sc_cli::{CliConfiguration, Result, SharedParams};
+use sp_core::{hashing::blake2_256, H256};
+
+/// Benchmark the quality of the coin-flipper randomness source.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct RandomnessCmd {
+	/// Number of synthetic blocks to toss a coin in.
+	#[arg(long, default_value_t = 10_000)]
+	pub blocks: BlockNumber,
+
+	/// Seed used to derive the synthetic parent block hashes.
+	#[arg(long, default_value_t = 0)]
+	pub seed: u64,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl RandomnessCmd {
+	/// Toss a coin in every synthetic block and print the statistics of the outcomes.
+	pub fn run(&self) -> Result<()> {
+		if self.blocks < 2 {
+			return Err("At least two blocks are needed to compute statistics.".into())
+		}
+
+		let storage = frame_system::GenesisConfig::<Runtime>::default()
+			.build_storage()
+			.map_err(|e| format!("Failed to build genesis storage: {}", e))?;
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		let samples = ext.execute_with(|| {
+			let account = AccountId::new([0u8; 32]);
+			let mut parent_hash = H256::from(blake2_256(&self.seed.encode()));
+			(1..=self.blocks)
+				.map(|number| {
+					frame_system::Pallet::<Runtime>::initialize(
+						&number,
+						&parent_hash,
+						&Default::default(),
+					);
+					AllPalletsWithSystem::on_initialize(number);
+
+					// Toss the way `do_toss` does, for an account tossing once per block.
+					let sample = TemplateModule::toss_outcome(&account, 0) == CoinSide::Head;
+					parent_hash = H256::from(blake2_256(&(self.seed, number).encode()));
+					sample
+				})
+				.collect::<Vec<_>>()
+		});
+
+		let stats = RandomnessStats::new(&samples);
+		println!("samples:            {}", stats.samples);
+		println!("heads (true):       {}", stats.ones);
+		println!("bias:               {:+.6} (z = {:+.3})", stats.bias, stats.bias_z);
+		println!(
+			"runs:               {} (expected {:.1}, z = {:+.3})",
+			stats.runs, stats.expected_runs, stats.runs_z
+		);
+		println!("serial correlation: {:+.6}", stats.serial_correlation);
+		println!("chi-square (1 bit): {:.3} (1 dof, 5% critical 3.841)", stats.chi_square_bits);
+		println!("chi-square (pairs): {:.3} (3 dof, 5% critical 7.815)", stats.chi_square_pairs);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for RandomnessCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
+
+/// Statistical tests of a sequence of coin tosses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomnessStats {
+	/// Number of tosses.
+	pub samples: usize,
+	/// Number of `true` outcomes.
+	pub ones: usize,
+	/// Fraction of `true` outcomes minus one half.
+	pub bias: f64,
+	/// Standard score of the number of `true` outcomes.
+	pub bias_z: f64,
+	/// Number of runs of equal consecutive outcomes.
+	pub runs: usize,
+	/// Expected number of runs for a random sequence with the same number of ones.
+	pub expected_runs: f64,
+	/// Standard score of the Wald-Wolfowitz runs test.
+	pub runs_z: f64,
+	/// Lag-one autocorrelation of the sequence.
+	pub serial_correlation: f64,
+	/// Chi-square statistic of single outcomes against a fair coin.
+	pub chi_square_bits: f64,
+	/// Chi-square statistic of non-overlapping pairs of outcomes against a uniform distribution.
+	pub chi_square_pairs: f64,
+}
+
+impl RandomnessStats {
+	/// Compute the statistics of the given outcomes.
+	pub fn new(samples: &[bool]) -> Self {
+		let n = samples.len() as f64;
+		let ones = samples.iter().filter(|s| **s).count();
+		let zeros = samples.len() - ones;
+		let (n1, n0) = (ones as f64, zeros as f64);
+
+		let bias = n1 / n - 0.5;
+		let bias_z = (n1 - n / 2.0) / (n / 4.0).sqrt();
+
+		let runs = 1 + samples.windows(2).filter(|w| w[0] != w[1]).count();
+		let expected_runs = 2.0 * n1 * n0 / n + 1.0;
+		let runs_variance = (expected_runs - 1.0) * (expected_runs - 2.0) / (n - 1.0);
+		let runs_z = if runs_variance > 0.0 {
+			(runs as f64 - expected_runs) / runs_variance.sqrt()
+		} else {
+			f64::NAN
+		};
+
+		let mean = n1 / n;
+		let centered = |s: &bool| if *s { 1.0 - mean } else { -mean };
+		let variance: f64 = samples.iter().map(|s| centered(s).powi(2)).sum();
+		let covariance: f64 = samples.windows(2).map(|w| centered(&w[0]) * centered(&w[1])).sum();
+		let serial_correlation = if variance > 0.0 { covariance / variance } else { f64::NAN };
+
+		let chi_square_bits = ((n1 - n / 2.0).powi(2) + (n0 - n / 2.0).powi(2)) / (n / 2.0);
+
+		let mut pairs = [0usize; 4];
+		for pair in samples.chunks_exact(2) {
+			pairs[((pair[0] as usize) << 1) | pair[1] as usize] += 1;
+		}
+		let expected_pairs = (samples.len() / 2) as f64 / 4.0;
+		let chi_square_pairs = pairs
+			.iter()
+			.map(|observed| (*observed as f64 - expected_pairs).powi(2) / expected_pairs)
+			.sum();
+
+		Self {
+			samples: samples.len(),
+			ones,
+			bias,
+			bias_z,
+			runs,
+			expected_runs,
+			runs_z,
+			serial_correlation,
+			chi_square_bits,
+			chi_square_pairs,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_close(actual: f64, expected: f64) {
+		assert!((actual - expected).abs() < 1e-6, "{} is not {}", actual, expected);
+	}
+
+	#[test]
+	fn all_heads_are_maximally_biased() {
+		let stats = RandomnessStats::new(&[true; 8]);
+
+		assert_eq!((stats.samples, stats.ones, stats.runs), (8, 8, 1));
+		assert_close(stats.bias, 0.5);
+		// (8 - 4) / sqrt(8 / 4)
+		assert_close(stats.bias_z, 2.0 * 2f64.sqrt());
+		assert_close(stats.expected_runs, 1.0);
+		// A constant sequence has no run or correlation statistics
+		assert!(stats.runs_z.is_nan());
+		assert!(stats.serial_correlation.is_nan());
+		// ((8 - 4)² + (0 - 4)²) / 4
+		assert_close(stats.chi_square_bits, 8.0);
+		// Four head-head pairs out of an expected one of each: (3 × 1² + 3²) / 1
+		assert_close(stats.chi_square_pairs, 12.0);
+	}
+
+	#[test]
+	fn alternating_tosses_have_too_many_runs() {
+		let samples: Vec<bool> = (0..8).map(|i| i % 2 == 0).collect();
+		let stats = RandomnessStats::new(&samples);
+
+		assert_eq!((stats.samples, stats.ones, stats.runs), (8, 4, 8));
+		assert_close(stats.bias, 0.0);
+		assert_close(stats.bias_z, 0.0);
+		// 2 × 4 × 4 / 8 + 1, with a variance of 4 × 3 / 7
+		assert_close(stats.expected_runs, 5.0);
+		assert_close(stats.runs_z, 3.0 / (12.0f64 / 7.0).sqrt());
+		// Seven products of -1/4, over a variance of 8 × 1/4
+		assert_close(stats.serial_correlation, -0.875);
+		assert_close(stats.chi_square_bits, 0.0);
+		// Four head-tail pairs out of an expected one of each
+		assert_close(stats.chi_square_pairs, 12.0);
+	}
+
+	#[test]
+	fn balanced_tosses_pass_the_chi_square_tests() {
+		// Each pair of outcomes occurs once
+		let samples = [true, true, true, false, false, true, false, false];
+		let stats = RandomnessStats::new(&samples);
+
+		assert_eq!((stats.samples, stats.ones, stats.runs), (8, 4, 4));
+		assert_close(stats.bias, 0.0);
+		assert_close(stats.bias_z, 0.0);
+		assert_close(stats.expected_runs, 5.0);
+		assert_close(stats.runs_z, -1.0 / (12.0f64 / 7.0).sqrt());
+		// Four products of 1/4 and three of -1/4, over a variance of 8 × 1/4
+		assert_close(stats.serial_correlation, 0.125);
+		assert_close(stats.chi_square_bits, 0.0);
+		assert_close(stats.chi_square_pairs, 0.0);
+	}
+}
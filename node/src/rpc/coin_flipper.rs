@@ -0,0 +1,396 @@
+//! RPC methods for querying coin-flipper activity.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use frame_support::{storage::storage_prefix, Blake2_128Concat, StorageHasher};
+use frame_system::{EventRecord, Phase};
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::{RpcResult, SubscriptionResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+	SubscriptionSink,
+};
+use node_template_runtime::{
+	opaque::Block, pallet_template, AccountId, Balance, BlockNumber, Hash, Runtime, RuntimeEvent,
+	TemplateModule,
+};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+
+/// The maximum number of blocks a single query may scan.
+pub const MAX_BLOCK_RANGE: BlockNumber = 10_000;
+
+/// The maximum number of blocks a single query may scan on nodes that deny unsafe RPC calls.
+pub const MAX_SAFE_BLOCK_RANGE: BlockNumber = 100;
+
+/// A coin-flipper event together with where it was emitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinEventRecord {
+	/// Number of the block that contains the event.
+	pub block_number: BlockNumber,
+	/// Hash of the block that contains the event.
+	pub block_hash: Hash,
+	/// Index of the extrinsic that emitted the event, if any.
+	pub extrinsic_index: Option<u32>,
+	/// Name of the event, e.g. `CoinTossed`.
+	pub event: String,
+	/// The account the event is about.
+	pub account: AccountId,
+	/// Side of the coin after the event, for flips and tosses.
+	pub side: Option<String>,
+	/// Toss nonce used for the randomness, for tosses.
+	pub nonce: Option<u32>,
+}
+
+impl CoinEventRecord {
+	/// Builds a record from a `pallet_template` event, or `None` for unknown events.
+	pub fn new(
+		block_number: BlockNumber,
+		block_hash: Hash,
+		extrinsic_index: Option<u32>,
+		event: pallet_template::Event<Runtime>,
+	) -> Option<Self> {
+		let (event, account, side, nonce) = match event {
+			pallet_template::Event::CoinCreated { who } => ("CoinCreated", who, None, None),
+			pallet_template::Event::CoinFlipped { who, side } =>
+				("CoinFlipped", who, Some(side), None),
+			pallet_template::Event::CoinTossed { who, side, nonce } =>
+				("CoinTossed", who, Some(side), Some(nonce)),
+			pallet_template::Event::CoinRemoved { who } => ("CoinRemoved", who, None, None),
+			pallet_template::Event::CoinExpired { who } => ("CoinExpired", who, None, None),
+			_ => return None,
+		};
+
+		Some(Self {
+			block_number,
+			block_hash,
+			extrinsic_index,
+			event: event.into(),
+			account,
+			side: side.map(|side| format!("{:?}", side)),
+			nonce,
+		})
+	}
+}
+
+/// Where the block of a [`CoinOutcome`] stands in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutcomeStatus {
+	/// The block became part of the best chain.
+	Best,
+	/// The block left the best chain because of a reorg, its outcomes no longer hold.
+	Retracted,
+	/// The block was finalized, its outcomes are final.
+	Finalized,
+}
+
+/// The outcome of a flip or toss, as streamed by `coinflipper_subscribeOutcomes`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinOutcome {
+	/// Where the block of the outcome stands in the chain.
+	pub status: OutcomeStatus,
+	/// Whether the coin was flipped or tossed.
+	pub mode: String,
+	/// What the bet the toss settled paid out, zero if the bet was lost. `None` if the toss
+	/// settled no bet.
+	pub payout: Option<Balance>,
+	/// The event the outcome was decoded from.
+	#[serde(flatten)]
+	pub record: CoinEventRecord,
+}
+
+/// Coin-flipper RPC methods.
+#[rpc(server)]
+pub trait CoinFlipperApi {
+	/// Returns the coin events of `account` in blocks `from` to `to`, both inclusive.
+	///
+	/// `to` defaults to the best block. Blocks whose state was pruned are skipped. At most
+	/// [`MAX_BLOCK_RANGE`] blocks can be queried at once, or [`MAX_SAFE_BLOCK_RANGE`] on nodes
+	/// that deny unsafe RPC calls.
+	#[method(name = "coinflipper_accountEvents")]
+	fn account_events(
+		&self,
+		account: AccountId,
+		from: BlockNumber,
+		to: Option<BlockNumber>,
+	) -> RpcResult<Vec<CoinEventRecord>>;
+
+	/// Streams the outcome of every flip and toss, optionally only those of `account`.
+	///
+	/// Outcomes are sent when their block becomes best, again when it is finalized, and are
+	/// reported as retracted when a reorg drops their block from the best chain.
+	#[subscription(
+		name = "coinflipper_subscribeOutcomes" => "coinflipper_outcome",
+		unsubscribe = "coinflipper_unsubscribeOutcomes",
+		item = CoinOutcome,
+	)]
+	fn subscribe_outcomes(&self, account: Option<AccountId>);
+}
+
+/// Provides the coin-flipper RPC methods.
+pub struct CoinFlipper<C, B> {
+	client: Arc<C>,
+	executor: SubscriptionTaskExecutor,
+	deny_unsafe: DenyUnsafe,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> CoinFlipper<C, B> {
+	/// Creates a new instance of the coin-flipper RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		executor: SubscriptionTaskExecutor,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { client, executor, deny_unsafe, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The requested block range is invalid or too large.
+	InvalidRange,
+	/// Reading or decoding the chain state failed.
+	StateError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::InvalidRange => 1,
+			Error::StateError => 2,
+		}
+	}
+}
+
+fn rpc_error(error: Error, message: &str, data: impl ToString) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(error.into(), message, Some(data.to_string()))).into()
+}
+
+impl<C, B> CoinFlipperApiServer for CoinFlipper<C, B>
+where
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+	C: StorageProvider<Block, B>
+		+ HeaderBackend<Block>
+		+ BlockchainEvents<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+{
+	fn account_events(
+		&self,
+		account: AccountId,
+		from: BlockNumber,
+		to: Option<BlockNumber>,
+	) -> RpcResult<Vec<CoinEventRecord>> {
+		let to = to.unwrap_or_else(|| self.client.info().best_number);
+		if from > to || to - from >= MAX_BLOCK_RANGE {
+			return Err(rpc_error(
+				Error::InvalidRange,
+				"Invalid block range",
+				format!("from {} to {}, at most {} blocks", from, to, MAX_BLOCK_RANGE),
+			))
+		}
+		// Scanning the state of many blocks is expensive, public nodes only allow short ranges.
+		if to - from >= MAX_SAFE_BLOCK_RANGE {
+			self.deny_unsafe.check_if_safe()?;
+		}
+
+		// `EventTopics` is reset every block, so it has to be read at each block of the range.
+		let topic = TemplateModule::account_topic(&account);
+		let mut topic_key = storage_prefix(b"System", b"EventTopics").to_vec();
+		topic_key.extend(Blake2_128Concat::hash(&topic.0));
+		let topic_key = StorageKey(topic_key);
+
+		let mut records = Vec::new();
+		for number in from..=to {
+			let Some(hash) = self
+				.client
+				.hash(number)
+				.map_err(|e| rpc_error(Error::StateError, "Unable to read block hash", e))?
+			else {
+				break
+			};
+
+			// The state of pruned blocks can no longer be read, they are skipped.
+			let Ok(indices) =
+				read_storage::<_, _, Vec<(BlockNumber, u32)>>(&*self.client, hash, &topic_key)
+			else {
+				continue
+			};
+			let indices = indices.unwrap_or_default();
+			if indices.is_empty() {
+				continue
+			}
+			let Ok(events) = coin_events_at(&*self.client, number, hash) else { continue };
+			records.extend(
+				indices
+					.into_iter()
+					.filter_map(|(_, index)| events.iter().find(|(i, _)| *i == index))
+					.map(|(_, record)| record.clone()),
+			);
+		}
+
+		Ok(records)
+	}
+
+	fn subscribe_outcomes(
+		&self,
+		mut sink: SubscriptionSink,
+		account: Option<AccountId>,
+	) -> SubscriptionResult {
+		// New best blocks, together with the blocks a reorg retracted or enacted on the way.
+		let best = self.client.import_notification_stream().filter_map(|notification| {
+			let blocks = notification.is_new_best.then(|| {
+				let (retracted, enacted) = match &notification.tree_route {
+					Some(route) => (route.retracted().to_vec(), route.enacted().to_vec()),
+					None => Default::default(),
+				};
+				retracted
+					.into_iter()
+					.map(|block| (block.hash, OutcomeStatus::Retracted))
+					.chain(enacted.into_iter().map(|block| (block.hash, OutcomeStatus::Best)))
+					.chain(std::iter::once((notification.hash, OutcomeStatus::Best)))
+					.collect::<Vec<_>>()
+			});
+			future::ready(blocks)
+		});
+
+		// Newly finalized blocks, including the ones finalized implicitly.
+		let finalized = self.client.finality_notification_stream().map(|notification| {
+			notification
+				.tree_route
+				.iter()
+				.cloned()
+				.chain(std::iter::once(notification.hash))
+				.map(|hash| (hash, OutcomeStatus::Finalized))
+				.collect::<Vec<_>>()
+		});
+
+		let client = self.client.clone();
+		let outcomes = stream::select(best, finalized)
+			.flat_map(move |blocks| {
+				let outcomes = blocks
+					.into_iter()
+					.flat_map(|(hash, status)| outcomes_at(&*client, hash, status))
+					.filter(|outcome| {
+						account.as_ref().map_or(true, |a| *a == outcome.record.account)
+					})
+					.collect::<Vec<_>>();
+				stream::iter(outcomes)
+			})
+			.boxed();
+
+		let fut = async move {
+			sink.pipe_from_stream(outcomes).await;
+		};
+		self.executor.spawn("coinflipper-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
+
+/// Returns the outcomes of the flips and tosses in the given block.
+///
+/// Blocks whose state is not available, e.g. because it was pruned, have no outcomes.
+fn outcomes_at<C, B>(client: &C, hash: Hash, status: OutcomeStatus) -> Vec<CoinOutcome>
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B> + HeaderBackend<Block>,
+{
+	let Ok(Some(number)) = client.number(hash) else { return Vec::new() };
+	let events = template_events_at(client, hash).unwrap_or_default();
+	events
+		.iter()
+		.enumerate()
+		.filter_map(|(position, (_, extrinsic_index, event))| {
+			let record = CoinEventRecord::new(number, hash, *extrinsic_index, event.clone())?;
+			let mode = match record.event.as_str() {
+				"CoinFlipped" => "flip",
+				"CoinTossed" => "toss",
+				_ => return None,
+			};
+			// A toss that settles a bet is directly followed by the pallet's `BetSettled`.
+			let payout = match events.get(position + 1) {
+				Some((_, _, pallet_template::Event::BetSettled { who, payout, .. }))
+					if mode == "toss" && *who == record.account =>
+					Some(*payout),
+				_ => None,
+			};
+			Some(CoinOutcome { status, mode: mode.into(), payout, record })
+		})
+		.collect()
+}
+
+/// Reads the `pallet_template` events deposited in the given block, with their index among all
+/// its events and the index of the extrinsic that emitted them, if any.
+fn template_events_at<C, B>(
+	client: &C,
+	hash: Hash,
+) -> Result<Vec<(u32, Option<u32>, pallet_template::Event<Runtime>)>, String>
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B>,
+{
+	let events: Vec<EventRecord<RuntimeEvent, Hash>> =
+		read_storage(client, hash, &events_key())?.unwrap_or_default();
+
+	Ok(events
+		.into_iter()
+		.enumerate()
+		.filter_map(|(index, record)| {
+			let RuntimeEvent::TemplateModule(event) = record.event else { return None };
+			let extrinsic_index = match record.phase {
+				Phase::ApplyExtrinsic(index) => Some(index),
+				_ => None,
+			};
+			Some((index as u32, extrinsic_index, event))
+		})
+		.collect())
+}
+
+/// Reads the coin events deposited in the given block, with their index among all its events.
+pub fn coin_events_at<C, B>(
+	client: &C,
+	number: BlockNumber,
+	hash: Hash,
+) -> Result<Vec<(u32, CoinEventRecord)>, String>
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B>,
+{
+	Ok(template_events_at(client, hash)?
+		.into_iter()
+		.filter_map(|(index, extrinsic_index, event)| {
+			CoinEventRecord::new(number, hash, extrinsic_index, event).map(|record| (index, record))
+		})
+		.collect())
+}
+
+/// Reads and decodes a storage value at the given block.
+pub fn read_storage<C, B, T>(client: &C, hash: Hash, key: &StorageKey) -> Result<Option<T>, String>
+where
+	B: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, B>,
+	T: Decode,
+{
+	client
+		.storage(hash, key)
+		.map_err(|e| e.to_string())?
+		.map(|data| T::decode(&mut &data.0[..]))
+		.transpose()
+		.map_err(|e| e.to_string())
+}
+
+/// Storage key of the events of a block.
+pub fn events_key() -> StorageKey {
+	StorageKey(storage_prefix(b"System", b"Events").to_vec())
+}
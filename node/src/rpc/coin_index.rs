@@ -0,0 +1,82 @@
+//! RPC methods for querying the node's coin-flipper event index.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::AccountId;
+
+use crate::coin_index::{CoinIndex, EventFilter, IndexedEvent};
+
+/// Coin-flipper index RPC methods.
+#[rpc(server)]
+pub trait CoinIndexApi {
+	/// Returns the indexed events of `account` that match `filter`, oldest first.
+	///
+	/// Only events in finalized blocks are indexed.
+	#[method(name = "coinflipper_indexedEvents")]
+	fn indexed_events(
+		&self,
+		account: AccountId,
+		filter: Option<EventFilter>,
+	) -> RpcResult<Vec<IndexedEvent>>;
+}
+
+/// Provides the coin-flipper index RPC methods.
+pub struct CoinIndexRpc {
+	index: Arc<CoinIndex>,
+}
+
+impl CoinIndexRpc {
+	/// Creates a new instance of the coin-flipper index RPC handler.
+	pub fn new(index: Arc<CoinIndex>) -> Self {
+		Self { index }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The filter is invalid.
+	InvalidFilter,
+	/// Querying the index failed.
+	IndexError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::InvalidFilter => 1,
+			Error::IndexError => 2,
+		}
+	}
+}
+
+impl CoinIndexApiServer for CoinIndexRpc {
+	fn indexed_events(
+		&self,
+		account: AccountId,
+		filter: Option<EventFilter>,
+	) -> RpcResult<Vec<IndexedEvent>> {
+		let filter = filter.unwrap_or_default();
+		if let Some(side) = filter.side.as_deref().filter(|side| !["Head", "Tail"].contains(side)) {
+			return Err(CallError::Custom(ErrorObject::owned(
+				Error::InvalidFilter.into(),
+				"Invalid side, expected Head or Tail",
+				Some(side.to_string()),
+			))
+			.into())
+		}
+
+		self.index.events(&account, &filter).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::IndexError.into(),
+				"Unable to query the coin index",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+}
@@ -1,16 +1,26 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use futures::FutureExt;
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use futures::{channel::mpsc, stream, FutureExt, StreamExt};
+use node_template_runtime::{self, opaque::Block, Hash, RuntimeApi};
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
+pub use sc_consensus_manual_seal::EngineCommand;
+use sc_consensus_manual_seal::{consensus::aura::AuraConsensusDataProvider, ManualSealParams};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use std::{
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+use substrate_prometheus_endpoint::Registry;
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -36,6 +46,7 @@ pub(crate) type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+type FullPool = sc_transaction_pool::FullPool<Block, FullClient>;
 
 #[allow(clippy::type_complexity)]
 pub fn new_partial(
@@ -46,7 +57,7 @@ pub fn new_partial(
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block, FullClient>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		FullPool,
 		(
 			sc_consensus_grandpa::GrandpaBlockImport<
 				FullBackend,
@@ -140,7 +151,13 @@ pub fn new_partial(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// With `coin_index` set, the coin-flipper events of finalized blocks are indexed into a SQLite
+/// database at that path and can be queried over RPC.
+pub fn new_full(
+	config: Configuration,
+	coin_index: Option<PathBuf>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -208,16 +225,15 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
-	let rpc_extensions_builder = {
-		let client = client.clone();
-		let pool = transaction_pool.clone();
-
-		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
-			crate::rpc::create_full(deps).map_err(Into::into)
-		})
-	};
+	let coin_index = spawn_coin_tasks(
+		&task_manager,
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		coin_index,
+	)?;
+	let rpc_extensions_builder =
+		rpc_extensions_builder(client.clone(), transaction_pool.clone(), coin_index);
 
 	let _rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		network: network.clone(),
@@ -329,3 +345,184 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	network_starter.start_network();
 	Ok(task_manager)
 }
+
+// Starts the coin-flipper metrics, when Prometheus is enabled, and the event indexer, when
+// `coin_index` is set. Returns the opened index, for the RPC.
+fn spawn_coin_tasks(
+	task_manager: &TaskManager,
+	client: Arc<FullClient>,
+	transaction_pool: Arc<FullPool>,
+	prometheus_registry: Option<&Registry>,
+	coin_index: Option<PathBuf>,
+) -> Result<Option<Arc<crate::coin_index::CoinIndex>>, ServiceError> {
+	if let Some(registry) = prometheus_registry {
+		let metrics = crate::coin_metrics::CoinMetrics::register(registry)?;
+		task_manager.spawn_handle().spawn(
+			"coin-metrics",
+			None,
+			crate::coin_metrics::run(client.clone(), transaction_pool, metrics),
+		);
+	}
+
+	let Some(path) = coin_index else { return Ok(None) };
+	let index = Arc::new(crate::coin_index::CoinIndex::open(&path).map_err(|e| {
+		ServiceError::Other(format!("Failed to open coin index {:?}: {}", path, e))
+	})?);
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"coin-index",
+		None,
+		crate::coin_index::run(client, index.clone()),
+	);
+	Ok(Some(index))
+}
+
+// Builds the RPC extensions of a full node
+fn rpc_extensions_builder(
+	client: Arc<FullClient>,
+	pool: Arc<FullPool>,
+	coin_index: Option<Arc<crate::coin_index::CoinIndex>>,
+) -> Box<
+	dyn Fn(
+		crate::rpc::DenyUnsafe,
+		sc_rpc::SubscriptionTaskExecutor,
+	) -> Result<jsonrpsee::RpcModule<()>, ServiceError>,
+> {
+	Box::new(move |deny_unsafe, subscription_executor| {
+		let deps = crate::rpc::FullDeps {
+			client: client.clone(),
+			pool: pool.clone(),
+			deny_unsafe,
+			subscription_executor,
+			coin_index: coin_index.clone(),
+		};
+		crate::rpc::create_full::<_, _, FullBackend>(deps).map_err(Into::into)
+	})
+}
+
+/// Sends commands to the block authoring of a node started by [`new_manual_seal`].
+pub type SealCommands = mpsc::Sender<EngineCommand<Hash>>;
+
+/// Builds a new service for a full client that seals its own blocks, for tests.
+///
+/// Instead of Aura slots, a block is sealed as soon as a transaction enters the pool, and whenever
+/// an [`EngineCommand`] is sent through the returned [`SealCommands`]. Every sealed block is
+/// finalized right away, without GRANDPA, and is a slot after its parent, so that blocks can be
+/// sealed faster than the slot duration.
+pub fn new_manual_seal(
+	config: Configuration,
+	coin_index: Option<PathBuf>,
+) -> Result<(TaskManager, SealCommands), ServiceError> {
+	let executor = sc_service::new_native_or_wasm_executor(&config);
+	let (client, backend, keystore_container, mut task_manager) =
+		sc_service::new_full_parts::<Block, RuntimeApi, _>(&config, None, executor)?;
+	let client = Arc::new(client);
+	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+		config.transaction_pool.clone(),
+		config.role.is_authority().into(),
+		config.prometheus_registry(),
+		task_manager.spawn_essential_handle(),
+		client.clone(),
+	);
+	let import_queue = sc_consensus_manual_seal::import_queue(
+		Box::new(client.clone()),
+		&task_manager.spawn_essential_handle(),
+		config.prometheus_registry(),
+	);
+
+	let net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
+	let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			net_config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue,
+			block_announce_validator_builder: None,
+			warp_sync_params: None,
+		})?;
+
+	let prometheus_registry = config.prometheus_registry().cloned();
+	let coin_index = spawn_coin_tasks(
+		&task_manager,
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		coin_index,
+	)?;
+	let rpc_extensions_builder =
+		rpc_extensions_builder(client.clone(), transaction_pool.clone(), coin_index);
+
+	let _rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		network,
+		client: client.clone(),
+		keystore: keystore_container.keystore(),
+		task_manager: &mut task_manager,
+		transaction_pool: transaction_pool.clone(),
+		rpc_builder: rpc_extensions_builder,
+		backend,
+		system_rpc_tx,
+		tx_handler_controller,
+		sync_service,
+		config,
+		telemetry: None,
+	})?;
+
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		None,
+	);
+
+	let (commands, manual_commands) = mpsc::channel(1024);
+	let instant_commands =
+		transaction_pool.pool().validated_pool().import_notification_stream().map(|_| {
+			EngineCommand::SealNewBlock {
+				create_empty: false,
+				finalize: true,
+				parent_hash: None,
+				sender: None,
+			}
+		});
+
+	// Each block gets the timestamp of the slot after its parent's
+	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+	let next_timestamp = Arc::new(AtomicU64::new(*sp_timestamp::Timestamp::current()));
+	let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+		block_import: client.clone(),
+		env: proposer_factory,
+		client: client.clone(),
+		pool: transaction_pool,
+		commands_stream: stream::select(instant_commands, manual_commands),
+		select_chain,
+		consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+		create_inherent_data_providers: move |_, ()| {
+			let next_timestamp = next_timestamp.clone();
+			async move {
+				let timestamp = sp_timestamp::InherentDataProvider::new(
+					next_timestamp.fetch_add(slot_duration.as_millis(), Ordering::SeqCst).into(),
+				);
+
+				let slot =
+					sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+						*timestamp,
+						slot_duration,
+					);
+
+				Ok((slot, timestamp))
+			}
+		},
+	});
+
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"manual-seal",
+		Some("block-authoring"),
+		manual_seal,
+	);
+
+	network_starter.start_network();
+	Ok((task_manager, commands))
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pallet_template::fuzzing::{run, Op};
+
+// Longer sequences only slow the fuzzer down without reaching new states
+const MAX_OPS: usize = 256;
+
+fuzz_target!(|ops: Vec<Op>| {
+	if ops.len() <= MAX_OPS {
+		run(&ops);
+	}
+});
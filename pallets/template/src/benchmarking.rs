@@ -5,30 +5,341 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Template;
 use frame_benchmarking::v2::*;
-use frame_system::RawOrigin;
+use frame_support::{
+	sp_runtime::traits::{Bounded, One, Saturating, Zero},
+	traits::{Currency, Get},
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+
+// Returns the whitelisted caller, owning a coin and enough funds for whatever it does
+fn coin_owner<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 4u32.into());
+	CoinStorage::<T>::insert(&caller, Coin::default());
+	caller
+}
+
+// Approves `count` operators for the coin of `owner`, and returns the last one
+fn approve_operators<T: Config>(owner: &T::AccountId, count: u32) -> T::AccountId {
+	let mut operator = account("operator", 0, 0);
+	for i in 0..count {
+		operator = account("operator", i, 0);
+		Template::<T>::approve_operator(
+			RawOrigin::Signed(owner.clone()).into(),
+			operator.clone(),
+			None,
+		)
+		.unwrap();
+	}
+	operator
+}
+
+// Gives the pot enough funds to take the largest bets and sweeps
+fn fund_pot<T: Config>() {
+	T::Currency::make_free_balance_be(
+		&Template::<T>::account_id(),
+		BalanceOf::<T>::max_value() / 4u32.into(),
+	);
+}
+
+// Opens a bet of `stake` for `bettor`, on the side the toss settling it lands on, so that the
+// settlement pays the bet out
+fn open_winning_bet<T: Config>(bettor: &T::AccountId, stake: BalanceOf<T>) {
+	let now = frame_system::Pallet::<T>::block_number();
+	frame_system::Pallet::<T>::set_block_number(Template::<T>::bet_settlement_block());
+	let side = Template::<T>::toss_outcome(bettor, TossNonce::<T>::get(bettor));
+	frame_system::Pallet::<T>::set_block_number(now);
+	Template::<T>::place_bet(RawOrigin::Signed(bettor.clone()).into(), side, stake).unwrap();
+}
+
+// Returns the shortest interval between scheduled tosses the rate limits allow for the longest
+// schedules
+fn toss_interval<T: Config>() -> BlockNumberFor<T> {
+	let per_window: BlockNumberFor<T> = T::MaxTossesPerWindow::get().max(1).into();
+	let window_interval = T::TossWindow::get().saturating_add(per_window - One::one()) / per_window;
+	T::MinBlocksBetweenTosses::get().max(window_interval).max(One::one())
+}
 
 #[benchmarks]
 mod benchmarks {
 	use super::*;
 
 	#[benchmark]
-	fn do_something() {
-		let value = 100u32.into();
+	fn create_coin() {
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()));
+
+		assert!(CoinStorage::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn do_flip() {
+		let caller = coin_owner::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()));
+
+		assert!(TossActivityOf::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn do_toss() {
+		let caller = coin_owner::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()));
+
+		assert_eq!(TossNonce::<T>::get(&caller), 1);
+	}
+
+	// The coin already has all but one of its operators
+	#[benchmark]
+	fn approve_operator() {
+		let owner = coin_owner::<T>();
+		approve_operators::<T>(&owner, T::MaxOperators::get() - 1);
+		let operator: T::AccountId = account("new operator", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner.clone()), operator.clone(), None);
+
+		assert!(Template::<T>::ensure_operator(&owner, &operator).is_ok());
+	}
+
+	// The revoked operator is the last of the most operators a coin can have
+	#[benchmark]
+	fn revoke_operator() {
+		let owner = coin_owner::<T>();
+		let operator = approve_operators::<T>(&owner, T::MaxOperators::get());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner.clone()), operator.clone());
+
+		assert!(Template::<T>::ensure_operator(&owner, &operator).is_err());
+	}
+
+	// The operator is the last of the most operators a coin can have
+	#[benchmark]
+	fn flip_for() {
+		let owner = coin_owner::<T>();
+		let operator = approve_operators::<T>(&owner, T::MaxOperators::get());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(operator), owner.clone());
+
+		assert!(TossActivityOf::<T>::contains_key(&owner));
+	}
+
+	// The operator is the last of the most operators a coin can have
+	#[benchmark]
+	fn toss_for() {
+		let owner = coin_owner::<T>();
+		let operator = approve_operators::<T>(&owner, T::MaxOperators::get());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(operator), owner.clone());
+
+		assert_eq!(TossNonce::<T>::get(&owner), 1);
+	}
+
+	// The caller already has all but one of its schedules, and schedules the most tosses
+	#[benchmark]
+	fn schedule_tosses() {
+		let caller = coin_owner::<T>();
+		for _ in 1..T::MaxTossSchedules::get() {
+			Template::<T>::schedule_tosses(
+				RawOrigin::Signed(caller.clone()).into(),
+				toss_interval::<T>(),
+				1,
+			)
+			.unwrap();
+		}
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), toss_interval::<T>(), T::MaxScheduledTosses::get());
+
+		assert_eq!(TossSchedules::<T>::get(&caller).len() as u32, T::MaxTossSchedules::get());
+	}
+
+	// The caller cancels the last of its schedules
+	#[benchmark]
+	fn cancel_toss_schedule() {
+		let caller = coin_owner::<T>();
+		for _ in 0..T::MaxTossSchedules::get() {
+			Template::<T>::schedule_tosses(
+				RawOrigin::Signed(caller.clone()).into(),
+				toss_interval::<T>(),
+				T::MaxScheduledTosses::get(),
+			)
+			.unwrap();
+		}
+		let id = T::MaxTossSchedules::get() - 1;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), id);
+
+		assert!(!TossSchedules::<T>::get(&caller).iter().any(|schedule| schedule.id == id));
+	}
+
+	#[benchmark]
+	fn set_sponsorship_quota() -> Result<(), BenchmarkError> {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, Some(1));
+
+		assert_eq!(SponsorshipQuota::<T>::get(), Some(1));
+		Ok(())
+	}
+
+	// The first deposit also mints the house its shares
+	#[benchmark]
+	fn deposit_bankroll() {
+		fund_pot::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = BalanceOf::<T>::max_value() / 16u32.into();
+		T::Currency::make_free_balance_be(&caller, amount + amount);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), amount);
+
+		assert!(!BankrollShares::<T>::get(&caller).is_zero());
+	}
+
+	// The caller redeems all of its shares, once its cooldown is over
+	#[benchmark]
+	fn withdraw_bankroll() {
+		fund_pot::<T>();
 		let caller: T::AccountId = whitelisted_caller();
+		let amount = BalanceOf::<T>::max_value() / 16u32.into();
+		T::Currency::make_free_balance_be(&caller, amount + amount);
+		Template::<T>::deposit_bankroll(RawOrigin::Signed(caller.clone()).into(), amount).unwrap();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::BankrollCooldown::get());
+		let shares = BankrollShares::<T>::get(&caller);
+
 		#[extrinsic_call]
-		do_something(RawOrigin::Signed(caller), value);
+		_(RawOrigin::Signed(caller.clone()), shares);
 
-		assert_eq!(Something::<T>::get(), Some(value));
+		assert!(!BankrollShares::<T>::contains_key(&caller));
 	}
 
+	// The sweep burns house shares, as a liquidity provider owns part of the pot
 	#[benchmark]
-	fn cause_error() {
-		Something::<T>::put(100u32);
+	fn sweep_pot() {
+		fund_pot::<T>();
+		let provider: T::AccountId = account("provider", 0, 0);
+		let amount = BalanceOf::<T>::max_value() / 16u32.into();
+		T::Currency::make_free_balance_be(&provider, amount + amount);
+		Template::<T>::deposit_bankroll(RawOrigin::Signed(provider).into(), amount).unwrap();
+		let house_shares = BankrollShares::<T>::get(&Template::<T>::account_id());
+
+		#[block]
+		{
+			Template::<T>::sweep_pot();
+		}
+
+		assert!(BankrollShares::<T>::get(&Template::<T>::account_id()) < house_shares);
+	}
+
+	#[benchmark]
+	fn cool_off() {
 		let caller: T::AccountId = whitelisted_caller();
+
 		#[extrinsic_call]
-		cause_error(RawOrigin::Signed(caller));
+		_(RawOrigin::Signed(caller.clone()), One::one());
+
+		assert!(Template::<T>::ensure_may_play(&caller).is_err());
+	}
+
+	#[benchmark]
+	fn self_exclude() {
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), One::one());
+
+		assert!(Template::<T>::ensure_may_play(&caller).is_err());
+	}
+
+	#[benchmark]
+	fn lift_self_exclusion() {
+		let caller: T::AccountId = whitelisted_caller();
+		Template::<T>::self_exclude(RawOrigin::Signed(caller.clone()).into(), One::one()).unwrap();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + One::one());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()));
+
+		assert!(Template::<T>::ensure_may_play(&caller).is_ok());
+	}
+
+	// Both limits are loosened, so the caller must be allowed to play
+	#[benchmark]
+	fn set_loss_limits() {
+		let caller: T::AccountId = whitelisted_caller();
+		Template::<T>::set_loss_limits(
+			RawOrigin::Signed(caller.clone()).into(),
+			Some(One::one()),
+			Some(One::one()),
+		)
+		.unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), None, None);
+
+		assert!(PlayerLimitsOf::<T>::get(&caller)
+			.is_some_and(|limits| limits.daily_loss_limit.is_none()));
+	}
+
+	// The bet is checked against both loss limits, over a full week of losses
+	#[benchmark]
+	fn place_bet() {
+		let caller = coin_owner::<T>();
+		fund_pot::<T>();
+		Template::<T>::set_loss_limits(
+			RawOrigin::Signed(caller.clone()).into(),
+			Some(BalanceOf::<T>::max_value()),
+			Some(BalanceOf::<T>::max_value()),
+		)
+		.unwrap();
+		RecentLosses::<T>::mutate(&caller, |buckets| {
+			for bucket in 0..responsible_gaming::MAX_LOSS_BUCKETS {
+				buckets.try_push((bucket.into(), Zero::zero())).unwrap();
+			}
+		});
+		let stake = Template::<T>::max_bet();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), CoinSide::Head, stake);
+
+		assert!(OpenBets::<T>::contains_key(&caller));
+	}
+
+	// Every settled bet is won, so that each one is paid out of the pot
+	#[benchmark]
+	fn settle_bets(b: Linear<0, { T::MaxBetsPerBlock::get() }>) {
+		fund_pot::<T>();
+		let stake = Template::<T>::max_bet() / T::MaxBetsPerBlock::get().max(1).into();
+		for i in 0..b {
+			let bettor: T::AccountId = account("bettor", i, 0);
+			T::Currency::make_free_balance_be(&bettor, stake + stake);
+			CoinStorage::<T>::insert(&bettor, Coin::default());
+			open_winning_bet::<T>(&bettor, stake);
+		}
+		let settle_at = Template::<T>::bet_settlement_block();
+		frame_system::Pallet::<T>::set_block_number(settle_at);
+
+		#[block]
+		{
+			Template::<T>::settle_due_bets(settle_at);
+		}
 
-		assert_eq!(Something::<T>::get(), Some(101u32));
+		assert_eq!(OpenBets::<T>::iter().count(), 0);
+		assert!(TotalExposure::<T>::get().is_zero());
 	}
 
 	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,124 @@
+//! Bets on coin tosses, paid out of the pot.
+//!
+//! A bet stakes a balance on the side an account's coin lands on. The stake moves into the pot
+//! when the bet is placed, and the bet is settled `BetSettlementDelay` blocks later, in
+//! `on_initialize`, by a toss of the coin that the pallet makes itself. That toss draws on the
+//! randomness of the settlement block, which is fixed when the bet is placed but only known once
+//! the blocks in between are built. The bettor's own tosses never settle a bet, so a bettor cannot
+//! wait for a toss it already knows it wins.
+//!
+//! A won bet pays out [`Pallet::payout_for`] its stake, stake included. A lost stake stays in the
+//! pot and counts against the bettor's loss limits. Until a bet is settled its payout is part of
+//! the pot's `TotalExposure`, which withdrawals, sweeps and sponsored fees always leave in the pot.
+
+use crate::{
+	BalanceOf, Bet, BetsDue, CoinSide, CoinStorage, Config, Error, Event, OpenBets, Pallet,
+	TotalExposure, WeightInfo,
+};
+use frame_support::{
+	ensure,
+	sp_runtime::{
+		traits::{One, Saturating, Zero},
+		DispatchResult,
+	},
+	traits::{Currency, ExistenceRequirement, Get},
+	weights::Weight,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+impl<T: Config> Pallet<T> {
+	/// Returns the block in which a bet placed in the current block is settled.
+	pub fn bet_settlement_block() -> BlockNumberFor<T> {
+		let now = frame_system::Pallet::<T>::block_number();
+		now.saturating_add(T::BetSettlementDelay::get().max(One::one()))
+	}
+
+	/// Moves `stake` from `who` into the pot, on the side `side` of the toss settling the bet.
+	pub fn do_place_bet(who: &T::AccountId, side: CoinSide, stake: BalanceOf<T>) -> DispatchResult {
+		ensure!(CoinStorage::<T>::contains_key(who), Error::<T>::CoinDoesNotExist);
+		Self::ensure_may_play(who)?;
+		ensure!(!OpenBets::<T>::contains_key(who), Error::<T>::BetAlreadyOpen);
+		ensure!(!stake.is_zero(), Error::<T>::InvalidStake);
+		Self::ensure_within_loss_limits(who, stake)?;
+		Self::ensure_within_risk_limit(stake)?;
+
+		let settle_at = Self::bet_settlement_block();
+		BetsDue::<T>::try_append(settle_at, who).map_err(|_| Error::<T>::TooManyBetsDue)?;
+		let payout = Self::payout_for(stake);
+		T::Currency::transfer(who, &Self::account_id(), stake, ExistenceRequirement::KeepAlive)?;
+		OpenBets::<T>::insert(who, Bet { side: side.clone(), stake, payout, settle_at });
+		TotalExposure::<T>::mutate(|exposure| *exposure = exposure.saturating_add(payout));
+
+		Self::deposit_account_event(
+			who,
+			Event::BetPlaced { who: who.clone(), side, stake, payout, settle_at },
+		);
+		Ok(())
+	}
+
+	/// Settles the bets due in block `now`, and returns the weight used.
+	pub fn settle_due_bets(now: BlockNumberFor<T>) -> Weight {
+		let due = BetsDue::<T>::take(now);
+		for who in &due {
+			Self::settle_bet(who, now);
+		}
+		T::WeightInfo::settle_bets(due.len() as u32)
+	}
+
+	// Tosses the coin of `who` to settle its bet due in block `now`. Won bets are paid out of the
+	// pot, lost ones count against the account's loss limits
+	fn settle_bet(who: &T::AccountId, now: BlockNumberFor<T>) {
+		// Bets of removed coins were forfeited, and a later bet settles in its own block
+		let Some(bet) = OpenBets::<T>::get(who).filter(|bet| bet.settle_at == now) else { return };
+		// Removing a coin forfeits its bet, so the coin exists
+		let Ok((side, nonce)) = Self::do_toss_coin(who) else { return };
+		Self::deposit_account_event(
+			who,
+			Event::CoinTossed { who: who.clone(), side: side.clone(), nonce },
+		);
+
+		let payout = if bet.side == side {
+			let pot = Self::account_id();
+			// The pot always keeps the exposure, and may pay out all of it, so this only fails if
+			// its balance is frozen. The bet then stays open, and is settled by a new toss in the
+			// next block with room
+			if T::Currency::transfer(&pot, who, bet.payout, ExistenceRequirement::AllowDeath)
+				.is_err()
+			{
+				Self::requeue_bet(who, bet);
+				return
+			}
+			bet.payout
+		} else {
+			Self::note_loss(who, bet.stake);
+			Zero::zero()
+		};
+		OpenBets::<T>::remove(who);
+		TotalExposure::<T>::mutate(|exposure| *exposure = exposure.saturating_sub(bet.payout));
+		Self::deposit_account_event(who, Event::BetSettled { who: who.clone(), side, payout });
+	}
+
+	// Settles the given bet of `who` in the first block after its settlement block with room
+	fn requeue_bet(who: &T::AccountId, mut bet: Bet<BalanceOf<T>, BlockNumberFor<T>>) {
+		loop {
+			bet.settle_at = bet.settle_at.saturating_add(One::one());
+			if BetsDue::<T>::try_append(bet.settle_at, who).is_ok() {
+				break
+			}
+		}
+		OpenBets::<T>::insert(who, bet);
+	}
+
+	/// Drops the open bet of `who`, if any, whose coin is removed. Its stake is forfeited to the
+	/// pot, and its payout no longer counts towards the exposure.
+	pub fn forfeit_bet(who: &T::AccountId) {
+		if let Some(bet) = OpenBets::<T>::take(who) {
+			TotalExposure::<T>::mutate(|exposure| *exposure = exposure.saturating_sub(bet.payout));
+		}
+	}
+
+	/// Returns the part of the pot that is not set aside to pay out open bets.
+	pub fn uncommitted_pot() -> BalanceOf<T> {
+		T::Currency::free_balance(&Self::account_id()).saturating_sub(TotalExposure::<T>::get())
+	}
+}
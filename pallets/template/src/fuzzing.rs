@@ -0,0 +1,136 @@
+//! Random call sequences against the mock runtime.
+//!
+//! [`run`] dispatches a sequence of [`Op`]s and panics as soon as one of the pallet invariants
+//! is violated. It is shared by the proptest suite and the `call_sequences` cargo-fuzz target.
+
+use crate::{mock::*, CoinSide, CoinStorage, Event};
+use frame_support::dispatch::DispatchResult;
+use sp_runtime::StateVersion;
+use std::collections::BTreeSet;
+
+// Number of distinct signed accounts the sequences are sent from
+pub const ACCOUNTS: u8 = 8;
+
+// Origin of a call, signed accounts are taken modulo `ACCOUNTS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub enum Origin {
+	Signed(u8),
+	None,
+	Root,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub enum Action {
+	Create,
+	Flip,
+	Toss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub enum Op {
+	// Dispatch a call of the pallet
+	Call { origin: Origin, action: Action },
+	// Move on to the next block
+	NextBlock,
+}
+
+fn opposite(side: &CoinSide) -> CoinSide {
+	match side {
+		CoinSide::Head => CoinSide::Tail,
+		CoinSide::Tail => CoinSide::Head,
+	}
+}
+
+// Returns the events of the pallet deposited in the current block
+fn coin_events() -> Vec<Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::TemplateModule(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+fn dispatch(origin: Origin, action: Action) -> DispatchResult {
+	let origin = match origin {
+		Origin::Signed(who) => RuntimeOrigin::signed(u64::from(who % ACCOUNTS) + 1),
+		Origin::None => RuntimeOrigin::none(),
+		Origin::Root => RuntimeOrigin::root(),
+	};
+	match action {
+		Action::Create => TemplateModule::create_coin(origin),
+		Action::Flip => TemplateModule::do_flip(origin),
+		Action::Toss => TemplateModule::do_toss(origin),
+	}
+}
+
+// Dispatches `ops` in a fresh mock runtime, checking the invariants after every call:
+// - only signed accounts that created a coin own one,
+// - failed calls change neither storage nor events,
+// - every successful call deposits exactly one event, matching the change in storage,
+// - flipping turns the coin to the other side, so flipping twice is a no-op.
+pub fn run(ops: &[Op]) {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let mut owners = BTreeSet::new();
+
+		for op in ops {
+			let (origin, action) = match *op {
+				Op::Call { origin, action } => (origin, action),
+				Op::NextBlock => {
+					System::set_block_number(System::block_number() + 1);
+					System::reset_events();
+					continue
+				},
+			};
+
+			let who = match origin {
+				Origin::Signed(who) => Some(u64::from(who % ACCOUNTS) + 1),
+				_ => None,
+			};
+			let before = who.and_then(CoinStorage::<Test>::get);
+			let root = sp_io::storage::root(StateVersion::V1);
+			let events = coin_events().len();
+
+			let result = dispatch(origin, action);
+			let new_events = coin_events().split_off(events);
+
+			let Some(who) = who.filter(|_| result.is_ok()) else {
+				assert!(result.is_err(), "{:?} succeeded without a signed origin", op);
+				assert_eq!(sp_io::storage::root(StateVersion::V1), root, "{:?} changed storage", op);
+				assert!(new_events.is_empty(), "{:?} deposited events", op);
+				continue
+			};
+
+			let after = CoinStorage::<Test>::get(who).expect("successful calls leave a coin; qed");
+			let expected = match action {
+				Action::Create => {
+					assert!(before.is_none(), "{:?} replaced a coin", op);
+					assert_eq!(after.side(), &CoinSide::default());
+					owners.insert(who);
+					Event::CoinCreated { who }
+				},
+				Action::Flip => {
+					let before = before.expect("flips need a coin; qed");
+					assert_eq!(after.side(), &opposite(before.side()), "{:?} did not flip", op);
+					Event::CoinFlipped { who, side: after.side().clone() }
+				},
+				Action::Toss => {
+					let nonce = match new_events.as_slice() {
+						[Event::CoinTossed { nonce, .. }] => *nonce,
+						_ => panic!("{:?} deposited {:?}", op, new_events),
+					};
+					Event::CoinTossed { who, side: after.side().clone(), nonce }
+				},
+			};
+			assert_eq!(new_events, vec![expected], "{:?} deposited unexpected events", op);
+
+			let holders: BTreeSet<u64> = CoinStorage::<Test>::iter_keys().collect();
+			assert_eq!(holders, owners, "coins are not held by their creators");
+		}
+	});
+}
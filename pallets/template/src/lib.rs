@@ -4,27 +4,53 @@ pub use pallet::*;
 
 use frame_system::pallet_prelude::OriginFor;
 
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzzing;
+
+#[cfg(test)]
+mod proptests;
+
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 pub mod weights;
 pub use weights::*;
 
+pub mod sponsorship;
+pub use sponsorship::SponsoredTransactionCharge;
+
+pub mod rate_limit;
+pub use rate_limit::CheckTossRate;
+
+pub mod risk;
+
+pub mod responsible_gaming;
+
+pub mod bets;
+
 type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+type BalanceOf<T> =
+	<<T as Config>::Currency as frame_support::traits::Currency<AccountIdOf<T>>>::Balance;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::sp_runtime::traits::AccountIdConversion;
+	use frame_support::sp_runtime::traits::{
+		AccountIdConversion, CheckedDiv, Dispatchable, Hash as HashT, SaturatedConversion, Saturating, Zero,
+	};
+	use frame_support::sp_runtime::{helpers_128bit::multiply_by_rational_with_rounding, Permill, Rounding};
 	use frame_support::pallet_prelude::{OptionQuery, *};
 	use frame_support::PalletId;
-	use frame_support::traits::Randomness;
-	use frame_system::pallet_prelude::*;
+	use frame_support::traits::{
+		schedule::{v3::Named as ScheduleNamed, DispatchTime, LOWEST_PRIORITY},
+		Currency, ExistenceRequirement, IsSubType, OnKilledAccount, Randomness, StorePreimage,
+	};
+	use frame_system::{pallet_prelude::*, RawOrigin};
 	
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
@@ -40,10 +66,105 @@ pub mod pallet {
 		type PalletId: Get<PalletId>;
 
 		type MyRandomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
+
+		// Number of `do_toss`/`do_flip` calls per account and era whose fees are paid by the pot,
+		// unless the admin origin set another quota with `set_sponsorship_quota`
+		#[pallet::constant]
+		type SponsoredCallsPerEra: Get<u32>;
+
+		// Length in blocks of a sponsorship era. Zero disables sponsorship
+		#[pallet::constant]
+		type SponsorshipEra: Get<BlockNumberFor<Self>>;
+
+		// Minimum number of blocks between two flips or tosses of the same account
+		#[pallet::constant]
+		type MinBlocksBetweenTosses: Get<BlockNumberFor<Self>>;
+
+		// Maximum number of flips and tosses of an account within a `TossWindow`
+		#[pallet::constant]
+		type MaxTossesPerWindow: Get<u32>;
+
+		// Length in blocks of the rate limiting window. Zero disables the per-window cap
+		#[pallet::constant]
+		type TossWindow: Get<BlockNumberFor<Self>>;
+
+		// Number of blocks without a flip or toss after which a coin expires and is removed in
+		// `on_idle`. Zero disables expiry
+		#[pallet::constant]
+		type InactivityPeriod: Get<BlockNumberFor<Self>>;
+
+		// Maximum number of operators approved for one coin at a time
+		#[pallet::constant]
+		type MaxOperators: Get<u32>;
+
+		// The overarching call type, so that tosses can be scheduled
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin>
+			+ From<Call<Self>>;
+
+		// The origin scheduled tosses are dispatched from
+		type PalletsOrigin: From<RawOrigin<Self::AccountId>>;
+
+		// The scheduler dispatching recurring tosses
+		type Scheduler: ScheduleNamed<BlockNumberFor<Self>, <Self as Config>::RuntimeCall, Self::PalletsOrigin>;
+
+		// Where scheduled calls are stored
+		type Preimages: StorePreimage;
+
+		// Maximum number of active toss schedules per account
+		#[pallet::constant]
+		type MaxTossSchedules: Get<u32>;
+
+		// Maximum number of tosses in one schedule
+		#[pallet::constant]
+		type MaxScheduledTosses: Get<u32>;
+
+		// Fee per scheduled toss, paid to the pot up front since scheduled tosses pay no
+		// transaction fees
+		#[pallet::constant]
+		type TossScheduleFee: Get<BalanceOf<Self>>;
+
+		// The origin allowed to change the coin-flipper parameters
+		type AdminOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		// The currency held by the pot
+		type Currency: Currency<Self::AccountId>;
+
+		// The account the pot balance above `PotReserveTarget` is swept to
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		// Balance the pot keeps when it is swept
+		#[pallet::constant]
+		type PotReserveTarget: Get<BalanceOf<Self>>;
+
+		// Number of blocks between two sweeps of the pot. Zero disables sweeping
+		#[pallet::constant]
+		type SweepPeriod: Get<BlockNumberFor<Self>>;
+
+		// Number of blocks after its last bankroll deposit before an account can withdraw
+		#[pallet::constant]
+		type BankrollCooldown: Get<BlockNumberFor<Self>>;
+
+		// The house's expected return per unit staked, which bounds bets to this fraction of the pot
+		#[pallet::constant]
+		type HouseEdge: Get<Permill>;
+
+		// Number of blocks in a day for the rolling windows of loss limits
+		#[pallet::constant]
+		type LossLimitDay: Get<BlockNumberFor<Self>>;
+
+		// Number of blocks between a bet and the toss settling it, at least one. The toss uses the
+		// randomness of its own block, which is not known yet when the bet is placed
+		#[pallet::constant]
+		type BetSettlementDelay: Get<BlockNumberFor<Self>>;
+
+		// Maximum number of bets settled in one block
+		#[pallet::constant]
+		type MaxBetsPerBlock: Get<u32>;
 	}
 
 	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialOrd, Default)]
-	enum CoinSide {
+	pub enum CoinSide {
 		#[default]
 		Head,
 		Tail,
@@ -52,23 +173,257 @@ pub mod pallet {
 	pub struct Coin {
 		side: CoinSide,
 	}
+
+	impl Coin {
+		// The side the coin is currently showing
+		pub fn side(&self) -> &CoinSide {
+			&self.side
+		}
+	}
+
+	// Recent flip and toss activity of an account, used for rate limiting
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct TossActivity<BlockNumber> {
+		// Block of the last flip or toss
+		pub last: BlockNumber,
+		// Block at which the current window started
+		pub window_start: BlockNumber,
+		// Flips and tosses in the current window
+		pub count: u32,
+	}
 	
+	// An account approved to flip and toss a coin on behalf of its owner
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct OperatorApproval<AccountId, BlockNumber> {
+		pub operator: AccountId,
+		// Last block in which the approval can be used, or None if it never expires
+		pub expiry: Option<BlockNumber>,
+	}
+
+	// Recurring tosses scheduled by an account
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct TossSchedule<BlockNumber> {
+		// Identifier of the schedule among those of the account
+		pub id: u32,
+		// Blocks between two tosses
+		pub every: BlockNumber,
+		// Block of the last toss
+		pub last: BlockNumber,
+	}
+
+	// A bet of an account on the side its coin lands on when the pallet tosses it to settle the bet
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+	pub struct Bet<Balance, BlockNumber> {
+		pub side: CoinSide,
+		// Balance moved into the pot when the bet was placed
+		pub stake: Balance,
+		// Balance the pot pays out if the toss lands on `side`, stake included
+		pub payout: Balance,
+		// Block in which the bet is settled
+		pub settle_at: BlockNumber,
+	}
+
+	// Limits an account set on its own play
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo, Default)]
+	pub struct PlayerLimits<BlockNumber, Balance> {
+		// Block from which the account may play again after cooling off
+		pub cooling_off_until: Option<BlockNumber>,
+		// Block from which the account may lift its self-exclusion, or None if it is not excluded
+		pub excluded_until: Option<BlockNumber>,
+		// Losses allowed in any `LossLimitDay` blocks
+		pub daily_loss_limit: Option<Balance>,
+		// Losses allowed in any seven `LossLimitDay` blocks
+		pub weekly_loss_limit: Option<Balance>,
+	}
+
+	impl<BlockNumber, Balance: PartialOrd + Copy> PlayerLimits<BlockNumber, Balance> {
+		// Returns true if the new loss limits allow more losses than these in either window
+		pub fn is_loosened_by(&self, daily: Option<Balance>, weekly: Option<Balance>) -> bool {
+			let looser = |old: Option<Balance>, new: Option<Balance>| match (old, new) {
+				(Some(_), None) => true,
+				(Some(old), Some(new)) => new > old,
+				(None, _) => false,
+			};
+			looser(self.daily_loss_limit, daily) || looser(self.weekly_loss_limit, weekly)
+		}
+	}
+
 	// The pallet's runtime storage items.
 	//
 	// StorageMap { Account => Coin }: Each Account has a Coin
 	#[pallet::storage]
 	pub type CoinStorage<T> = StorageMap<_, Blake2_128Concat, AccountIdOf<T>, Coin, OptionQuery>;
 
+	// StorageMap { Account => u32 }: How many times each Account has tossed its coin
+	#[pallet::storage]
+	pub type TossNonce<T> = StorageMap<_, Blake2_128Concat, AccountIdOf<T>, u32, ValueQuery>;
+
+	// StorageMap { Account => (Era, Calls) }: How many calls of each Account the pot has paid for
+	// in the given sponsorship era
+	#[pallet::storage]
+	pub type SponsoredCalls<T> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, (BlockNumberFor<T>, u32), ValueQuery>;
+
+	// StorageMap { Account => TossActivity }: When each Account last flipped or tossed its coin
+	#[pallet::storage]
+	pub type TossActivityOf<T> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, TossActivity<BlockNumberFor<T>>, OptionQuery>;
+
+	// StorageMap { Account => BlockNumber }: When each Account last created, flipped or tossed
+	// its coin. Coins without an entry, e.g. genesis coins, were last active at block zero
+	#[pallet::storage]
+	pub type CoinLastActive<T> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, BlockNumberFor<T>, ValueQuery>;
+
+	// StorageMap { Account => [OperatorApproval] }: The operators each Account approved for its coin
+	#[pallet::storage]
+	pub type CoinOperators<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AccountIdOf<T>,
+		BoundedVec<OperatorApproval<AccountIdOf<T>, BlockNumberFor<T>>, T::MaxOperators>,
+		ValueQuery,
+	>;
+
+	// StorageMap { Account => [TossSchedule] }: The recurring tosses each Account scheduled,
+	// including those that have run out since the Account last scheduled or cancelled one
+	#[pallet::storage]
+	pub type TossSchedules<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AccountIdOf<T>,
+		BoundedVec<TossSchedule<BlockNumberFor<T>>, T::MaxTossSchedules>,
+		ValueQuery,
+	>;
+
+	// StorageMap { Account => u32 }: The identifier of the next toss schedule of each Account
+	#[pallet::storage]
+	pub type NextTossScheduleId<T> = StorageMap<_, Blake2_128Concat, AccountIdOf<T>, u32, ValueQuery>;
+
+	// The number of sponsored calls per account and era set by the admin origin, or None to use
+	// `SponsoredCallsPerEra`
+	#[pallet::storage]
+	pub type SponsorshipQuota<T> = StorageValue<_, u32, OptionQuery>;
+
+	// StorageMap { Account => Balance }: The bankroll shares of each Account. The shares of the pot
+	// account are the house's own stake in the pot
+	#[pallet::storage]
+	pub type BankrollShares<T> = StorageMap<_, Blake2_128Concat, AccountIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	// The total number of bankroll shares, or zero while the pot has no liquidity providers
+	#[pallet::storage]
+	pub type TotalBankrollShares<T> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	// StorageMap { Account => BlockNumber }: The block of the last bankroll deposit of each Account
+	#[pallet::storage]
+	pub type LastBankrollDeposit<T> = StorageMap<_, Blake2_128Concat, AccountIdOf<T>, BlockNumberFor<T>, OptionQuery>;
+
+	// StorageMap { Account => PlayerLimits }: The limits each Account set on its own play. They are
+	// kept when the account is reaped, so emptying it does not lift them
+	#[pallet::storage]
+	pub type PlayerLimitsOf<T> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, PlayerLimits<BlockNumberFor<T>, BalanceOf<T>>, OptionQuery>;
+
+	// StorageMap { Account => [(BlockNumber, Balance)] }: The losses of each Account in the last
+	// week, summed per bucket starting at the given block
+	#[pallet::storage]
+	pub type RecentLosses<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AccountIdOf<T>,
+		BoundedVec<(BlockNumberFor<T>, BalanceOf<T>), ConstU32<{ responsible_gaming::MAX_LOSS_BUCKETS }>>,
+		ValueQuery,
+	>;
+
+	// StorageMap { Account => Bet }: The bet each Account placed and that is not settled yet
+	#[pallet::storage]
+	pub type OpenBets<T> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, Bet<BalanceOf<T>, BlockNumberFor<T>>, OptionQuery>;
+
+	// StorageMap { BlockNumber => [Account] }: The accounts whose bets are settled in each block.
+	// Bets forfeited since they were placed are skipped
+	#[pallet::storage]
+	pub type BetsDue<T: Config> =
+		StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, BoundedVec<AccountIdOf<T>, T::MaxBetsPerBlock>, ValueQuery>;
+
+	// The sum of the payouts of all open bets, which the pot keeps to pay them out
+	#[pallet::storage]
+	pub type TotalExposure<T> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	// The raw `CoinStorage` key after which `on_idle` resumes looking for expired coins, or None
+	// to start over from the first coin
+	#[pallet::storage]
+	pub type ExpiryCursor<T> = StorageValue<_, BoundedVec<u8, ConstU32<128>>, OptionQuery>;
+
+	// Accounts that own a coin at genesis.
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		pub coins: frame_support::sp_std::vec::Vec<AccountIdOf<T>>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for account_id in &self.coins {
+				CoinStorage::<T>::insert(account_id, Coin::default());
+			}
+		}
+	}
+
 	// Pallets use events to inform users when important changes are made.
+	//
+	// Coin events are indexed by the topic of the account they concern, see `account_topic`.
+	// Events that concern no account in particular are deposited without topics.
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		// Event emitted when a coin is created. 
 		CoinCreated { who: AccountIdOf<T> },
 		// Event emitted when a coin is flipped. 
-		CoinFlipped { who: AccountIdOf<T> },
-		// Event emitted when a coin is tossed. 
-		CoinTossed { who: AccountIdOf<T> },
+		CoinFlipped { who: AccountIdOf<T>, side: CoinSide },
+		// Event emitted when a coin is tossed. `nonce` is the account's toss counter that was
+		// mixed into the randomness, so the toss can be reproduced with `toss_outcome`
+		CoinTossed { who: AccountIdOf<T>, side: CoinSide, nonce: u32 },
+		// Event emitted when the coin of a reaped account is removed.
+		CoinRemoved { who: AccountIdOf<T> },
+		// Event emitted when a coin is removed after `InactivityPeriod` blocks without activity.
+		CoinExpired { who: AccountIdOf<T> },
+		// Event emitted when an owner approves an operator for its coin, or changes its expiry.
+		OperatorApproved { owner: AccountIdOf<T>, operator: AccountIdOf<T>, expiry: Option<BlockNumberFor<T>> },
+		// Event emitted when an owner revokes an operator of its coin.
+		OperatorRevoked { owner: AccountIdOf<T>, operator: AccountIdOf<T> },
+		// Event emitted when an account schedules `count` tosses, one every `every` blocks.
+		TossesScheduled { who: AccountIdOf<T>, id: u32, every: BlockNumberFor<T>, count: u32 },
+		// Event emitted when an account cancels the remaining tosses of a schedule.
+		TossScheduleCancelled { who: AccountIdOf<T>, id: u32 },
+		// Event emitted when the admin origin changes the sponsored calls per account and era.
+		// None means the quota is back to `SponsoredCallsPerEra`
+		SponsorshipQuotaSet { calls: Option<u32> },
+		// Event emitted when the pot balance above `PotReserveTarget` is moved to the treasury.
+		PotSwept { amount: BalanceOf<T> },
+		// Event emitted when an account deposits `amount` into the pot in exchange for `shares`.
+		BankrollDeposited { who: AccountIdOf<T>, amount: BalanceOf<T>, shares: BalanceOf<T> },
+		// Event emitted when an account redeems `shares` for `amount` of the pot.
+		BankrollWithdrawn { who: AccountIdOf<T>, shares: BalanceOf<T>, amount: BalanceOf<T> },
+		// Event emitted when an account pauses its play until block `until`.
+		CoolingOffStarted { who: AccountIdOf<T>, until: BlockNumberFor<T> },
+		// Event emitted when an account excludes itself, at least until block `until`.
+		SelfExcluded { who: AccountIdOf<T>, until: BlockNumberFor<T> },
+		// Event emitted when an account lifts its self-exclusion.
+		SelfExclusionLifted { who: AccountIdOf<T> },
+		// Event emitted when an account sets its daily and weekly loss limits.
+		LossLimitsSet { who: AccountIdOf<T>, daily: Option<BalanceOf<T>>, weekly: Option<BalanceOf<T>> },
+		// Event emitted when an account stakes `stake` on the side its coin lands on in block `settle_at`.
+		BetPlaced {
+			who: AccountIdOf<T>,
+			side: CoinSide,
+			stake: BalanceOf<T>,
+			payout: BalanceOf<T>,
+			settle_at: BlockNumberFor<T>,
+		},
+		// Event emitted when the toss settling a bet lands on `side`. `payout` is zero if the bet was lost
+		BetSettled { who: AccountIdOf<T>, side: CoinSide, payout: BalanceOf<T> },
 	}
 
 	// Errors inform users that something went wrong.
@@ -78,6 +433,69 @@ pub mod pallet {
 		CoinAlreadyExists,
 		// Error returned when a coin does not exist
 		CoinDoesNotExist,
+		// Error returned when an account flips or tosses too often
+		TooManyTosses,
+		// Error returned when a coin already has `MaxOperators` operators
+		TooManyOperators,
+		// Error returned when an account is not an operator of the coin, or its approval expired
+		NotAnOperator,
+		// Error returned when an approval would already be expired
+		ApprovalExpired,
+		// Error returned when a toss schedule has no tosses, more than `MaxScheduledTosses`, or tosses
+		// more often than rate limits allow
+		InvalidTossSchedule,
+		// Error returned when an account already has `MaxTossSchedules` active toss schedules
+		TooManyTossSchedules,
+		// Error returned when an account has no active toss schedule with the given identifier
+		TossScheduleNotFound,
+		// Error returned when a bankroll deposit or withdrawal is worth nothing at the current share price
+		InvalidBankrollAmount,
+		// Error returned when an account withdraws more bankroll shares than it owns
+		InsufficientShares,
+		// Error returned when an account withdraws within `BankrollCooldown` blocks of its last deposit
+		BankrollLocked,
+		// Error returned when a bet is larger than the pot can take, see `max_bet`
+		BetExceedsRiskLimit,
+		// Error returned when an account plays during its cooling-off period
+		CoolingOff,
+		// Error returned when a self-excluded account plays
+		SelfExcluded,
+		// Error returned when an account lifts its self-exclusion before the end of its term
+		SelfExclusionNotOver,
+		// Error returned when an account that is not self-excluded lifts its self-exclusion
+		NotSelfExcluded,
+		// Error returned when a bet could take an account's losses over its loss limits
+		LossLimitExceeded,
+		// Error returned when a cooling-off period or self-exclusion lasts no blocks
+		InvalidPlayerLimit,
+		// Error returned when an account places a bet while its previous one is still open
+		BetAlreadyOpen,
+		// Error returned when a bet stakes nothing
+		InvalidStake,
+		// Error returned when a bankroll withdrawal would leave the pot unable to pay out open bets
+		BetsUncovered,
+		// Error returned when `MaxBetsPerBlock` bets are already settled in the block a new bet would be
+		TooManyBetsDue,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// Settles the bets due in this block, then sweeps the pot into the treasury once every
+		// `SweepPeriod` blocks
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let weight = Self::settle_due_bets(now);
+			let period = T::SweepPeriod::get();
+			if period.is_zero() || !(now % period).is_zero() {
+				return weight
+			}
+			Self::sweep_pot();
+			weight.saturating_add(T::WeightInfo::sweep_pot())
+		}
+
+		// Spends leftover block weight on removing expired coins
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::expire_inactive_coins(now, remaining_weight)
+		}
 	}
 
 	#[pallet::call]
@@ -91,11 +509,11 @@ pub mod pallet {
 		/// - Event: `CoinCreated`
 		///
 		#[pallet::call_index(0)]
-		#[pallet::weight(T::WeightInfo::do_something())]
+		#[pallet::weight(T::WeightInfo::create_coin())]
 		pub fn create_coin(origin: OriginFor<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Self::do_create_coin(&who)?;
-			Self::deposit_event(Event::CoinCreated { who });
+			Self::deposit_account_event(&who, Event::CoinCreated { who: who.clone() });
 			Ok(())
 		}
 
@@ -107,11 +525,14 @@ pub mod pallet {
 		/// - Event: `CoinFlipped`
 		///
 		#[pallet::call_index(1)]
-		#[pallet::weight(T::WeightInfo::do_something())]
+		#[pallet::weight(T::WeightInfo::do_flip())]
 		pub fn do_flip(origin: OriginFor<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			Self::do_flip_coin(&who)?;
-			Self::deposit_event(Event::CoinFlipped { who });
+			Self::ensure_may_play(&who)?;
+			Self::ensure_toss_allowed(&who)?;
+			let side = Self::do_flip_coin(&who)?;
+			Self::note_toss(&who);
+			Self::deposit_account_event(&who, Event::CoinFlipped { who: who.clone(), side });
 			Ok(())
 		}
 
@@ -123,13 +544,426 @@ pub mod pallet {
 		/// - Event: `CoinTossed`
 		///
 		#[pallet::call_index(2)]
-		#[pallet::weight(T::WeightInfo::cause_error())]
+		#[pallet::weight(T::WeightInfo::do_toss())]
 		pub fn do_toss(origin: OriginFor<T>) -> DispatchResult {
 			let who : AccountIdOf<T> = ensure_signed(origin)?;
-			Self::do_toss_coin(&who)?;
-			Self::deposit_event(Event::CoinTossed { who });
+			Self::ensure_may_play(&who)?;
+			Self::ensure_toss_allowed(&who)?;
+			let (side, nonce) = Self::do_toss_coin(&who)?;
+			Self::note_toss(&who);
+			Self::deposit_account_event(&who, Event::CoinTossed { who: who.clone(), side, nonce });
+			Ok(())
+		}
+
+		/// Approve an operator to flip and toss the sender's coin on its behalf
+		///
+		/// - origin: The sender's account, which must own a coin
+		/// - who: The operator's account
+		/// - expiry: The last block in which the operator may act, or None for no expiry
+		///
+		/// Approving an operator again replaces the expiry of its approval
+		/// - Event: `OperatorApproved`
+		///
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::approve_operator())]
+		pub fn approve_operator(
+			origin: OriginFor<T>,
+			who: AccountIdOf<T>,
+			expiry: Option<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(CoinStorage::<T>::contains_key(&owner), Error::<T>::CoinDoesNotExist);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(expiry.map_or(true, |expiry| expiry >= now), Error::<T>::ApprovalExpired);
+
+			CoinOperators::<T>::try_mutate(&owner, |approvals| -> DispatchResult {
+				// Expired approvals are dropped so that they do not take up room
+				approvals.retain(|approval| approval.operator != who && Self::is_live(approval, now));
+				approvals
+					.try_push(OperatorApproval { operator: who.clone(), expiry })
+					.map_err(|_| Error::<T>::TooManyOperators)?;
+				Ok(())
+			})?;
+
+			Self::deposit_account_event(&owner, Event::OperatorApproved { owner: owner.clone(), operator: who, expiry });
+			Ok(())
+		}
+
+		/// Revoke an operator of the sender's coin
+		///
+		/// - origin: The sender's account
+		/// - who: The operator's account
+		///
+		/// - Event: `OperatorRevoked`
+		///
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::revoke_operator())]
+		pub fn revoke_operator(origin: OriginFor<T>, who: AccountIdOf<T>) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			CoinOperators::<T>::try_mutate_exists(&owner, |approvals| -> DispatchResult {
+				let list = approvals.as_mut().ok_or(Error::<T>::NotAnOperator)?;
+				let index = list
+					.iter()
+					.position(|approval| approval.operator == who)
+					.ok_or(Error::<T>::NotAnOperator)?;
+				list.remove(index);
+				if list.is_empty() {
+					*approvals = None;
+				}
+				Ok(())
+			})?;
+
+			Self::deposit_account_event(&owner, Event::OperatorRevoked { owner: owner.clone(), operator: who });
+			Ok(())
+		}
+
+		/// Flip the coin of `owner` as one of its operators
+		///
+		/// - origin: An operator approved by `owner`
+		/// - owner: The coin's owner
+		///
+		/// The owner's rate limits, cooling-off period and self-exclusion apply
+		/// - Event: `CoinFlipped`
+		///
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::flip_for())]
+		pub fn flip_for(origin: OriginFor<T>, owner: AccountIdOf<T>) -> DispatchResult {
+			let operator = ensure_signed(origin)?;
+			Self::ensure_operator(&owner, &operator)?;
+			Self::ensure_may_play(&owner)?;
+			Self::ensure_toss_allowed(&owner)?;
+			let side = Self::do_flip_coin(&owner)?;
+			Self::note_toss(&owner);
+			Self::deposit_account_event(&owner, Event::CoinFlipped { who: owner.clone(), side });
 			Ok(())
 		}
+
+		/// Toss the coin of `owner` as one of its operators
+		///
+		/// - origin: An operator approved by `owner`
+		/// - owner: The coin's owner
+		///
+		/// The owner's rate limits, cooling-off period and self-exclusion apply
+		/// - Event: `CoinTossed`
+		///
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::toss_for())]
+		pub fn toss_for(origin: OriginFor<T>, owner: AccountIdOf<T>) -> DispatchResult {
+			let operator = ensure_signed(origin)?;
+			Self::ensure_operator(&owner, &operator)?;
+			Self::ensure_may_play(&owner)?;
+			Self::ensure_toss_allowed(&owner)?;
+			let (side, nonce) = Self::do_toss_coin(&owner)?;
+			Self::note_toss(&owner);
+			Self::deposit_account_event(&owner, Event::CoinTossed { who: owner.clone(), side, nonce });
+			Ok(())
+		}
+
+		/// Schedule `count` tosses of the sender's coin, one every `every` blocks
+		///
+		/// - origin: The sender's account, which must own a coin
+		/// - every: Blocks between two tosses, starting `every` blocks from now
+		/// - count: Number of tosses, at most `MaxScheduledTosses`
+		///
+		/// The tosses are dispatched by the scheduler from the sender's account, and are subject
+		/// to its rate limits: `every` must be at least `MinBlocksBetweenTosses`, and no
+		/// `TossWindow` may hold more than `MaxTossesPerWindow` tosses of the schedule. The sender
+		/// pays `TossScheduleFee` per toss to the pot up front, which is not refunded if the
+		/// schedule is cancelled. It generates a new event with the
+		/// schedule's identifier
+		/// - Event: `TossesScheduled`
+		///
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::schedule_tosses())]
+		pub fn schedule_tosses(origin: OriginFor<T>, every: BlockNumberFor<T>, count: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(CoinStorage::<T>::contains_key(&who), Error::<T>::CoinDoesNotExist);
+			Self::ensure_may_play(&who)?;
+			ensure!(
+				count > 0 &&
+					count <= T::MaxScheduledTosses::get() &&
+					!every.is_zero() && every >= T::MinBlocksBetweenTosses::get(),
+				Error::<T>::InvalidTossSchedule
+			);
+			// A schedule that outpaces the window cap would pay for tosses that are then refused
+			let per_window = T::MaxTossesPerWindow::get();
+			ensure!(
+				T::TossWindow::get().is_zero() ||
+					count <= per_window ||
+					every.saturating_mul(per_window.into()) >= T::TossWindow::get(),
+				Error::<T>::InvalidTossSchedule
+			);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let first = now.saturating_add(every);
+			let last = first.saturating_add(every.saturating_mul((count - 1).into()));
+			let id = NextTossScheduleId::<T>::get(&who);
+
+			TossSchedules::<T>::try_mutate(&who, |schedules| -> DispatchResult {
+				Self::prune_toss_schedules(schedules, now);
+				schedules
+					.try_push(TossSchedule { id, every, last })
+					.map_err(|_| Error::<T>::TooManyTossSchedules)?;
+				Ok(())
+			})?;
+			NextTossScheduleId::<T>::insert(&who, id.wrapping_add(1));
+			let fee = T::TossScheduleFee::get().saturating_mul(count.into());
+			T::Currency::transfer(&who, &Self::account_id(), fee, ExistenceRequirement::KeepAlive)?;
+
+			let call = <T as Config>::RuntimeCall::from(Call::<T>::do_toss {});
+			T::Scheduler::schedule_named(
+				Self::toss_task_name(&who, id),
+				DispatchTime::At(first),
+				Some((every, count)),
+				LOWEST_PRIORITY,
+				RawOrigin::Signed(who.clone()).into(),
+				T::Preimages::bound(call)?,
+			)?;
+
+			Self::deposit_account_event(&who, Event::TossesScheduled { who: who.clone(), id, every, count });
+			Ok(())
+		}
+
+		/// Cancel the remaining tosses of one of the sender's toss schedules
+		///
+		/// - origin: The sender's account
+		/// - id: The identifier of the schedule, from its `TossesScheduled` event
+		///
+		/// - Event: `TossScheduleCancelled`
+		///
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::cancel_toss_schedule())]
+		pub fn cancel_toss_schedule(origin: OriginFor<T>, id: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			TossSchedules::<T>::try_mutate_exists(&who, |maybe_schedules| -> DispatchResult {
+				let schedules = maybe_schedules.as_mut().ok_or(Error::<T>::TossScheduleNotFound)?;
+				Self::prune_toss_schedules(schedules, now);
+				let index = schedules
+					.iter()
+					.position(|schedule| schedule.id == id)
+					.ok_or(Error::<T>::TossScheduleNotFound)?;
+				schedules.remove(index);
+				if schedules.is_empty() {
+					*maybe_schedules = None;
+				}
+				Ok(())
+			})?;
+			T::Scheduler::cancel_named(Self::toss_task_name(&who, id))?;
+
+			Self::deposit_account_event(&who, Event::TossScheduleCancelled { who: who.clone(), id });
+			Ok(())
+		}
+
+		/// Set how many flips and tosses per account and era the pot pays for
+		///
+		/// - origin: The admin origin
+		/// - calls: The new quota, or None to go back to `SponsoredCallsPerEra`
+		///
+		/// Calls already sponsored in the current era count against the new quota
+		/// - Event: `SponsorshipQuotaSet`
+		///
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::set_sponsorship_quota())]
+		pub fn set_sponsorship_quota(origin: OriginFor<T>, calls: Option<u32>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			SponsorshipQuota::<T>::set(calls);
+			Self::deposit_event(Event::SponsorshipQuotaSet { calls });
+			Ok(())
+		}
+
+		/// Deposit into the pot in exchange for bankroll shares
+		///
+		/// - origin: Signed origin of the liquidity provider
+		/// - amount: The balance to deposit
+		///
+		/// Shares are priced from the pot balance less the payouts set aside for open bets, so
+		/// liquidity providers share every gain and loss of the pot with the house, except for
+		/// sponsored fees which only the house pays for. The first deposit mints the house one share
+		/// per unit of the balance the pot already holds beyond those payouts
+		/// - Event: `BankrollDeposited`
+		///
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::deposit_bankroll())]
+		pub fn deposit_bankroll(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let pot = Self::account_id();
+
+			if TotalBankrollShares::<T>::get().is_zero() {
+				let house_shares = Self::uncommitted_pot();
+				BankrollShares::<T>::insert(&pot, house_shares);
+				TotalBankrollShares::<T>::put(house_shares);
+			}
+			let shares = Self::shares_for(amount);
+			ensure!(!shares.is_zero(), Error::<T>::InvalidBankrollAmount);
+
+			T::Currency::transfer(&who, &pot, amount, ExistenceRequirement::KeepAlive)?;
+			BankrollShares::<T>::mutate(&who, |owned| *owned = owned.saturating_add(shares));
+			TotalBankrollShares::<T>::mutate(|total| *total = total.saturating_add(shares));
+			LastBankrollDeposit::<T>::insert(&who, frame_system::Pallet::<T>::block_number());
+
+			Self::deposit_account_event(&who, Event::BankrollDeposited { who: who.clone(), amount, shares });
+			Ok(())
+		}
+
+		/// Redeem bankroll shares for their part of the pot
+		///
+		/// - origin: Signed origin of the liquidity provider
+		/// - shares: The number of shares to redeem
+		///
+		/// Withdrawals open `BankrollCooldown` blocks after the account's last deposit, and must
+		/// leave the pot enough to pay out every open bet
+		/// - Event: `BankrollWithdrawn`
+		///
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::withdraw_bankroll())]
+		pub fn withdraw_bankroll(origin: OriginFor<T>, shares: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owned = BankrollShares::<T>::get(&who);
+			ensure!(shares <= owned, Error::<T>::InsufficientShares);
+			if let Some(deposited) = LastBankrollDeposit::<T>::get(&who) {
+				let unlocked = deposited.saturating_add(T::BankrollCooldown::get());
+				ensure!(frame_system::Pallet::<T>::block_number() >= unlocked, Error::<T>::BankrollLocked);
+			}
+			let amount = Self::bankroll_value(shares);
+			ensure!(!amount.is_zero(), Error::<T>::InvalidBankrollAmount);
+			let pot = Self::account_id();
+			let left = T::Currency::free_balance(&pot).saturating_sub(amount);
+			ensure!(left >= TotalExposure::<T>::get(), Error::<T>::BetsUncovered);
+
+			T::Currency::transfer(&pot, &who, amount, ExistenceRequirement::KeepAlive)?;
+			if shares == owned {
+				BankrollShares::<T>::remove(&who);
+				LastBankrollDeposit::<T>::remove(&who);
+			} else {
+				BankrollShares::<T>::insert(&who, owned - shares);
+			}
+			TotalBankrollShares::<T>::mutate(|total| *total = total.saturating_sub(shares));
+
+			Self::deposit_account_event(&who, Event::BankrollWithdrawn { who: who.clone(), shares, amount });
+			Ok(())
+		}
+
+		/// Pause the sender's play for a number of blocks
+		///
+		/// - origin: The sender's account
+		/// - duration: Blocks during which the sender cannot flip or toss
+		///
+		/// The cooling-off period ends by itself, and can only be extended until then
+		/// - Event: `CoolingOffStarted`
+		///
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::cool_off())]
+		pub fn cool_off(origin: OriginFor<T>, duration: BlockNumberFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!duration.is_zero(), Error::<T>::InvalidPlayerLimit);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let mut limits = PlayerLimitsOf::<T>::get(&who).unwrap_or_default();
+			let until = now.saturating_add(duration).max(limits.cooling_off_until.unwrap_or(now));
+			limits.cooling_off_until = Some(until);
+			PlayerLimitsOf::<T>::insert(&who, limits);
+
+			Self::deposit_account_event(&who, Event::CoolingOffStarted { who: who.clone(), until });
+			Ok(())
+		}
+
+		/// Exclude the sender from play for at least a number of blocks
+		///
+		/// - origin: The sender's account
+		/// - duration: Blocks before the sender can lift the exclusion
+		///
+		/// The exclusion lasts until the sender lifts it with `lift_self_exclusion`, which is only
+		/// possible once its term is over. Its term can be extended, but never shortened
+		/// - Event: `SelfExcluded`
+		///
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::self_exclude())]
+		pub fn self_exclude(origin: OriginFor<T>, duration: BlockNumberFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!duration.is_zero(), Error::<T>::InvalidPlayerLimit);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let mut limits = PlayerLimitsOf::<T>::get(&who).unwrap_or_default();
+			let until = now.saturating_add(duration).max(limits.excluded_until.unwrap_or(now));
+			limits.excluded_until = Some(until);
+			PlayerLimitsOf::<T>::insert(&who, limits);
+
+			Self::deposit_account_event(&who, Event::SelfExcluded { who: who.clone(), until });
+			Ok(())
+		}
+
+		/// Lift the sender's self-exclusion once its term is over
+		///
+		/// - origin: The sender's account, which must be self-excluded
+		///
+		/// - Event: `SelfExclusionLifted`
+		///
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::lift_self_exclusion())]
+		pub fn lift_self_exclusion(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut limits = PlayerLimitsOf::<T>::get(&who).unwrap_or_default();
+			let until = limits.excluded_until.ok_or(Error::<T>::NotSelfExcluded)?;
+			ensure!(<frame_system::Pallet<T>>::block_number() >= until, Error::<T>::SelfExclusionNotOver);
+
+			limits.excluded_until = None;
+			PlayerLimitsOf::<T>::insert(&who, limits);
+
+			Self::deposit_account_event(&who, Event::SelfExclusionLifted { who: who.clone() });
+			Ok(())
+		}
+
+		/// Set the sender's daily and weekly loss limits
+		///
+		/// - origin: The sender's account
+		/// - daily: Losses allowed in any `LossLimitDay` blocks, or None for no limit
+		/// - weekly: Losses allowed in any seven `LossLimitDay` blocks, or None for no limit
+		///
+		/// Limits can always be tightened, but not loosened while cooling off or self-excluded
+		/// - Event: `LossLimitsSet`
+		///
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::set_loss_limits())]
+		pub fn set_loss_limits(
+			origin: OriginFor<T>,
+			daily: Option<BalanceOf<T>>,
+			weekly: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut limits = PlayerLimitsOf::<T>::get(&who).unwrap_or_default();
+			if limits.is_loosened_by(daily, weekly) {
+				Self::ensure_may_play(&who)?;
+			}
+
+			limits.daily_loss_limit = daily;
+			limits.weekly_loss_limit = weekly;
+			PlayerLimitsOf::<T>::insert(&who, limits);
+
+			Self::deposit_account_event(&who, Event::LossLimitsSet { who: who.clone(), daily, weekly });
+			Ok(())
+		}
+
+		/// Bet on the side the sender's coin lands on when the bet is settled
+		///
+		/// - origin: The sender's account, which must own a coin
+		/// - side: The side the sender bets on
+		/// - stake: The balance at stake, moved into the pot until the bet is settled
+		///
+		/// The stake must be within the sender's loss limits and `max_bet`. The pallet settles the
+		/// bet `BetSettlementDelay` blocks later by tossing the sender's coin with the randomness of
+		/// that block: it pays out twice the stake less the house edge if the toss lands on `side`,
+		/// and counts the stake against the sender's loss limits otherwise. The sender's own tosses
+		/// settle nothing
+		/// - Event: `BetPlaced`
+		///
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::place_bet())]
+		pub fn place_bet(origin: OriginFor<T>, side: CoinSide, stake: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_place_bet(&who, side, stake)
+		}
 	}
 	impl<T: Config> Pallet<T> {
 
@@ -138,6 +972,109 @@ pub mod pallet {
 			T::PalletId::get().into_account_truncating()
 		}
 
+		// Moves the house's stake in the pot above `PotReserveTarget` to the treasury, and returns the
+		// amount moved. Once the pot has liquidity providers, their shares are never swept, and the
+		// payouts of open bets always stay in the pot
+		pub fn sweep_pot() -> BalanceOf<T> {
+			let pot = Self::account_id();
+			let house_shares = BankrollShares::<T>::get(&pot);
+			let amount = Self::house_stake().saturating_sub(T::PotReserveTarget::get()).min(Self::uncommitted_pot());
+			if amount.is_zero() {
+				return amount
+			}
+			let burnt = Self::shares_for(amount).min(house_shares);
+
+			// The pot is kept alive, so this only fails if its balance is locked or the reserve target
+			// is below the existential deposit
+			match T::Currency::transfer(&pot, &T::TreasuryAccount::get(), amount, ExistenceRequirement::KeepAlive) {
+				Ok(()) => {
+					if !burnt.is_zero() {
+						BankrollShares::<T>::insert(&pot, house_shares - burnt);
+						TotalBankrollShares::<T>::mutate(|total| *total = total.saturating_sub(burnt));
+					}
+					Self::deposit_event(Event::PotSwept { amount });
+					amount
+				},
+				Err(_) => Zero::zero(),
+			}
+		}
+
+		// Returns the part of the pot owned by the house, which is the whole pot until the first
+		// bankroll deposit. The payouts set aside for open bets are owned by no one
+		pub fn house_stake() -> BalanceOf<T> {
+			let pot = Self::account_id();
+			if TotalBankrollShares::<T>::get().is_zero() {
+				Self::uncommitted_pot()
+			} else {
+				Self::bankroll_value(BankrollShares::<T>::get(&pot))
+			}
+		}
+
+		// Burns the house shares worth a fee the pot just sponsored, rounded up, so that the house
+		// alone pays for sponsorship and the shares of liquidity providers keep their value
+		pub fn charge_house(fee: BalanceOf<T>) {
+			let total = TotalBankrollShares::<T>::get();
+			if total.is_zero() || fee.is_zero() {
+				return
+			}
+			let pot = Self::account_id();
+			let house_shares = BankrollShares::<T>::get(&pot);
+			let balance = Self::uncommitted_pot().saturating_add(fee);
+			let burnt = multiply_by_rational_with_rounding(
+				fee.saturated_into(),
+				total.saturated_into(),
+				balance.saturated_into(),
+				Rounding::Up,
+			)
+			.map(|shares| shares.saturated_into())
+			.unwrap_or(house_shares)
+			.min(house_shares);
+			BankrollShares::<T>::insert(&pot, house_shares - burnt);
+			TotalBankrollShares::<T>::mutate(|total| *total = total.saturating_sub(burnt));
+		}
+
+		// Returns the part of the pot the given bankroll shares are worth, rounded down, once the
+		// payouts of open bets are set aside
+		pub fn bankroll_value(shares: BalanceOf<T>) -> BalanceOf<T> {
+			Self::pro_rata(shares, Self::uncommitted_pot(), TotalBankrollShares::<T>::get())
+		}
+
+		// Returns the bankroll shares a deposit of the given amount is worth, rounded down. Without
+		// shares the first deposit is priced one share per unit. Once the pot is drained, shares are
+		// worth nothing and none can be minted
+		pub fn shares_for(amount: BalanceOf<T>) -> BalanceOf<T> {
+			let total = TotalBankrollShares::<T>::get();
+			if total.is_zero() {
+				return amount
+			}
+			Self::pro_rata(amount, total, Self::uncommitted_pot())
+		}
+
+		// Returns `value * numerator / denominator` rounded down, or zero if `denominator` is zero
+		fn pro_rata(value: BalanceOf<T>, numerator: BalanceOf<T>, denominator: BalanceOf<T>) -> BalanceOf<T> {
+			multiply_by_rational_with_rounding(
+				value.saturated_into(),
+				numerator.saturated_into(),
+				denominator.saturated_into(),
+				Rounding::Down,
+			)
+			.map(|value| value.saturated_into())
+			.unwrap_or_else(Zero::zero)
+		}
+
+		// Returns the event topic under which all coin events of the given account are indexed.
+		// Coins are identified by their owner, so this is also the topic of the account's coin
+		pub fn account_topic(account_id: &T::AccountId) -> T::Hash {
+			T::Hashing::hash_of(&(T::PalletId::get(), account_id))
+		}
+
+		// Deposits an event indexed by the topic of the given account, so that clients can find
+		// the account's activity through `EventTopics` without scanning every event
+		pub(crate) fn deposit_account_event(account_id: &T::AccountId, event: Event<T>) {
+			let event = <T as Config>::RuntimeEvent::from(event);
+			<frame_system::Pallet<T>>::deposit_event_indexed(&[Self::account_topic(account_id)], event.into());
+		}
+
 		// This method creates a new coin for the given account
 		pub fn do_create_coin(account_id: &T::AccountId) -> DispatchResult {
 
@@ -148,11 +1085,12 @@ pub mod pallet {
 			
 			// Create a new coin
 			CoinStorage::<T>::insert(account_id, Coin::default());
+			Self::note_activity(account_id);
 			Ok(())
 		}
 
-		// This method flips the coin for the given account
-		pub fn do_flip_coin(account_id: &T::AccountId) -> DispatchResult {
+		// This method flips the coin for the given account and returns its new side
+		pub fn do_flip_coin(account_id: &T::AccountId) -> Result<CoinSide, DispatchError> {
 			
 			// If a coin does not exist, return an error
 			let mut coin = CoinStorage::<T>::get(account_id)
@@ -165,40 +1103,259 @@ pub mod pallet {
 			};
 			
 			// Update the coin
-			CoinStorage::<T>::insert(account_id, coin);
+			CoinStorage::<T>::insert(account_id, coin.clone());
+			Self::note_activity(account_id);
 			
-			Ok(())
+			Ok(coin.side)
 		}
 
-		// This method tosses the coin for the given account
-		pub fn do_toss_coin(account_id: &T::AccountId) -> DispatchResult {
+		// This method tosses the coin for the given account and returns its new side together with
+		// the toss nonce that was used
+		pub fn do_toss_coin(account_id: &T::AccountId) -> Result<(CoinSide, u32), DispatchError> {
 			let mut coin = CoinStorage::<T>::get(account_id)
 				.ok_or(Error::<T>::CoinDoesNotExist)?;
 
+			// Every toss of an account uses a new nonce, so that tosses in the same block differ
+			let nonce = TossNonce::<T>::get(account_id);
+			TossNonce::<T>::insert(account_id, nonce.wrapping_add(1));
+
+			let new_side = Self::toss_outcome(account_id, nonce);
+		
+			// Update the coin's side
+			coin.side = new_side.clone();
+			CoinStorage::<T>::insert(account_id, coin);
+			Self::note_activity(account_id);
+			
+			Ok((new_side, nonce))
+		}
+
+		// Returns whether an approval can still be used in the given block
+		fn is_live(approval: &OperatorApproval<T::AccountId, BlockNumberFor<T>>, now: BlockNumberFor<T>) -> bool {
+			approval.expiry.map_or(true, |expiry| now <= expiry)
+		}
+
+		// Checks that `operator` may currently act on the coin of `owner`
+		pub fn ensure_operator(owner: &T::AccountId, operator: &T::AccountId) -> DispatchResult {
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				CoinOperators::<T>::get(owner)
+					.iter()
+					.any(|approval| &approval.operator == operator && Self::is_live(approval, now)),
+				Error::<T>::NotAnOperator
+			);
+			Ok(())
+		}
+
+		// Returns the name under which the given toss schedule of an account is scheduled
+		fn toss_task_name(account_id: &T::AccountId, id: u32) -> [u8; 32] {
+			(T::PalletId::get(), b"toss", account_id, id).using_encoded(frame_support::sp_io::hashing::blake2_256)
+		}
+
+		// Drops the schedules whose last toss was dispatched. Scheduled calls run in
+		// `on_initialize`, so a schedule ending in the current block is over
+		fn prune_toss_schedules(
+			schedules: &mut BoundedVec<TossSchedule<BlockNumberFor<T>>, T::MaxTossSchedules>,
+			now: BlockNumberFor<T>,
+		) {
+			schedules.retain(|schedule| schedule.last > now);
+		}
+
+		// Records that the coin of the given account was used in the current block
+		fn note_activity(account_id: &T::AccountId) {
+			CoinLastActive::<T>::insert(account_id, <frame_system::Pallet<T>>::block_number());
+		}
+
+		// Removes the coin of the given account and everything the pallet tracks about it.
+		// An open bet can no longer be settled, so its stake is forfeited to the pot.
+		// Returns whether the account owned a coin
+		fn remove_coin(account_id: &T::AccountId) -> bool {
+			Self::forfeit_bet(account_id);
+			TossNonce::<T>::remove(account_id);
+			SponsoredCalls::<T>::remove(account_id);
+			TossActivityOf::<T>::remove(account_id);
+			CoinLastActive::<T>::remove(account_id);
+			CoinOperators::<T>::remove(account_id);
+			NextTossScheduleId::<T>::remove(account_id);
+			for schedule in TossSchedules::<T>::take(account_id) {
+				// Schedules that already ran out have nothing left to cancel
+				let _ = T::Scheduler::cancel_named(Self::toss_task_name(account_id, schedule.id));
+			}
+			CoinStorage::<T>::take(account_id).is_some()
+		}
+
+		// Removes the coins idle for more than `InactivityPeriod` blocks, within `remaining_weight`.
+		// Coins are visited in storage order, resuming where the previous call stopped.
+		// There are no coin deposits in this pallet yet, so expiring a coin refunds nothing
+		pub fn expire_inactive_coins(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			// Upper bound of coins visited in one call, whatever the weight left
+			const MAX_COINS_PER_CALL: u64 = 256;
+
+			let period = T::InactivityPeriod::get();
+			if period.is_zero() {
+				return Weight::zero()
+			}
+
+			// Reading and writing the cursor, then for each coin reading its last activity and open
+			// bet, removing up to nine storage items and cancelling its toss schedules
+			let db = T::DbWeight::get();
+			let base = db.reads_writes(1, 1);
+			let schedules = u64::from(T::MaxTossSchedules::get());
+			let per_coin = db.reads_writes(4 + 2 * schedules, 9 + 2 * schedules);
+			let Some(available) = remaining_weight.checked_sub(&base) else { return Weight::zero() };
+			let max_coins = available
+				.checked_div_per_component(&per_coin)
+				.unwrap_or(MAX_COINS_PER_CALL)
+				.min(MAX_COINS_PER_CALL);
+			if max_coins == 0 {
+				return Weight::zero()
+			}
+
+			let keys = match ExpiryCursor::<T>::get() {
+				Some(cursor) => CoinStorage::<T>::iter_keys_from(cursor.into_inner()),
+				None => CoinStorage::<T>::iter_keys(),
+			};
+			let accounts: frame_support::sp_std::vec::Vec<T::AccountId> = keys.take(max_coins as usize).collect();
+
+			// Resume after the last visited coin next time, or start over once all were visited
+			match accounts.last() {
+				Some(last) if accounts.len() as u64 == max_coins => {
+					let cursor = CoinStorage::<T>::hashed_key_for(last).try_into().ok();
+					ExpiryCursor::<T>::set(cursor);
+				},
+				_ => ExpiryCursor::<T>::kill(),
+			}
+
+			for account_id in &accounts {
+				// Coins with an open bet are kept until it is settled
+				if now.saturating_sub(CoinLastActive::<T>::get(account_id)) > period &&
+					!OpenBets::<T>::contains_key(account_id) &&
+					Self::remove_coin(account_id)
+				{
+					Self::deposit_account_event(account_id, Event::CoinExpired { who: account_id.clone() });
+				}
+			}
+
+			base.saturating_add(per_coin.saturating_mul(accounts.len() as u64))
+		}
+
+		// Returns the side a toss of the given account with the given nonce lands on in the current block
+		pub fn toss_outcome(account_id: &T::AccountId, nonce: u32) -> CoinSide {
 			let block_number = <frame_system::Pallet<T>>::block_number();
-			let seed = block_number.try_into().unwrap_or_else(|_| 0u32);
+			let seed: u32 = block_number.try_into().unwrap_or_else(|_| 0u32);
 
 			// Use the random value to decide the coin's new side
-			// This is very a simple approach that uses blocknunber as seed source. Never use it in production. 
-			let new_side = if Self::generate_insecure_random_boolean(seed) == true {
+			// This is very a simple approach that uses blocknunber, account and nonce as seed source. Never use it in production. 
+			if Self::generate_insecure_random_boolean(&(seed, account_id, nonce)) {
 				CoinSide::Head
 			} else {
 				CoinSide::Tail
+			}
+		}
+
+		// Returns the current sponsorship era, or None if sponsorship is disabled
+		fn sponsorship_era() -> Option<BlockNumberFor<T>> {
+			<frame_system::Pallet<T>>::block_number().checked_div(&T::SponsorshipEra::get())
+		}
+
+		// Returns how many calls per account and era the pot pays for
+		pub fn sponsorship_quota() -> u32 {
+			SponsorshipQuota::<T>::get().unwrap_or_else(T::SponsoredCallsPerEra::get)
+		}
+
+		// Returns how many sponsored calls the given account has left in the current era
+		pub fn sponsored_calls_left(account_id: &T::AccountId) -> u32 {
+			let Some(era) = Self::sponsorship_era() else { return 0 };
+			let used = match SponsoredCalls::<T>::get(account_id) {
+				(last_era, used) if last_era == era => used,
+				_ => 0,
 			};
-		
-			// Update the coin's side
-			coin.side = new_side;
-			CoinStorage::<T>::insert(account_id, coin);
-			
+			Self::sponsorship_quota().saturating_sub(used)
+		}
+
+		// Returns whether the pot may pay the fees of `call` sent by the given account.
+		// Only flips and tosses of an existing coin are sponsored, within the era quota
+		pub fn is_sponsorable(account_id: &T::AccountId, call: &<T as frame_system::Config>::RuntimeCall) -> bool
+		where
+			<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
+		{
+			// Coins are paid for by their owners, so new accounts cannot be made to drain the pot
+			let sponsorable = matches!(call.is_sub_type(), Some(Call::do_flip {}) | Some(Call::do_toss {}));
+			sponsorable && CoinStorage::<T>::contains_key(account_id) && Self::sponsored_calls_left(account_id) > 0
+		}
+
+		// Records that the pot paid for one more call of the given account
+		pub fn note_sponsored_call(account_id: &T::AccountId) {
+			let Some(era) = Self::sponsorship_era() else { return };
+			SponsoredCalls::<T>::mutate(account_id, |(last_era, used)| {
+				if *last_era != era {
+					*last_era = era;
+					*used = 0;
+				}
+				*used = used.saturating_add(1);
+			});
+		}
+
+		// Returns the rate limiting window that is current for the given activity
+		fn current_window(activity: &TossActivity<BlockNumberFor<T>>) -> (BlockNumberFor<T>, u32) {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let window = T::TossWindow::get();
+			if window.is_zero() || now >= activity.window_start.saturating_add(window) {
+				(now, 0)
+			} else {
+				(activity.window_start, activity.count)
+			}
+		}
+
+		// Checks that the given account may flip or toss its coin in the current block
+		pub fn ensure_toss_allowed(account_id: &T::AccountId) -> DispatchResult {
+			let Some(activity) = TossActivityOf::<T>::get(account_id) else { return Ok(()) };
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			// Still cooling down since the last flip or toss
+			ensure!(
+				now >= activity.last.saturating_add(T::MinBlocksBetweenTosses::get()),
+				Error::<T>::TooManyTosses
+			);
+
+			// Too many flips and tosses in the current window
+			let (_, count) = Self::current_window(&activity);
+			ensure!(
+				T::TossWindow::get().is_zero() || count < T::MaxTossesPerWindow::get(),
+				Error::<T>::TooManyTosses
+			);
+
 			Ok(())
 		}
 
-		// You should call this function with different seed values 	
-		pub fn generate_insecure_random_boolean(seed: u32) -> bool {
-			let (random_seed, _) = T::MyRandomness::random(&(T::PalletId::get(), seed).encode());
+		// Records a flip or toss of the given account for rate limiting
+		fn note_toss(account_id: &T::AccountId) {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let activity = match TossActivityOf::<T>::get(account_id) {
+				Some(activity) => {
+					let (window_start, count) = Self::current_window(&activity);
+					TossActivity { last: now, window_start, count: count.saturating_add(1) }
+				},
+				None => TossActivity { last: now, window_start: now, count: 1 },
+			};
+			TossActivityOf::<T>::insert(account_id, activity);
+		}
+
+		// You should call this function with different subjects 	
+		pub fn generate_insecure_random_boolean<S: Encode>(subject: &S) -> bool {
+			let (random_seed, _) = T::MyRandomness::random(&(T::PalletId::get(), subject).encode());
 			let random_number = <u32>::decode(&mut random_seed.as_ref())
 				.expect("secure hashes should always be bigger than u32; qed");
 			random_number % 2 == 0
 		}
 	}
+
+	// When an account is reaped, its coin and everything the pallet tracks about it are removed,
+	// so that dusted accounts do not leave state behind forever
+	impl<T: Config> OnKilledAccount<T::AccountId> for Pallet<T> {
+		fn on_killed_account(who: &T::AccountId) {
+			if Self::remove_coin(who) {
+				Self::deposit_account_event(who, Event::CoinRemoved { who: who.clone() });
+			}
+		}
+	}
 }
@@ -1,9 +1,12 @@
 use crate as pallet_template;
-use frame_support::{parameter_types,traits::{ConstU16, ConstU64}, PalletId};
+use crate::CoinSide;
+use frame_support::{parameter_types,traits::{ConstU16, ConstU32, ConstU64, ConstU8, EqualPrivilegeOnly}, weights::{IdentityFee, RuntimeDbWeight, Weight}, PalletId};
+use frame_system::EnsureRoot;
+use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 use sp_core::H256;
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	traits::{BlakeTwo256, IdentityLookup, One},
+	BuildStorage, Permill,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -13,7 +16,11 @@ frame_support::construct_runtime!(
 	pub enum Test
 	{
 		System: frame_system,
+		Balances: pallet_balances,
+		TransactionPayment: pallet_transaction_payment,
 		TemplateModule: pallet_template,
+		Preimage: pallet_preimage,
+		Scheduler: pallet_scheduler,
 	}
 );
 
@@ -21,7 +28,7 @@ impl frame_system::Config for Test {
 	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = MockDbWeight;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
 	type Nonce = u64;
@@ -34,30 +41,177 @@ impl frame_system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
-	type OnKilledAccount = ();
+	type OnKilledAccount = TemplateModule;
 	type SystemWeightInfo = ();
 	type SS58Prefix = ConstU16<42>;
 	type OnSetCode = ();
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = ();
+	type MaxHolds = ();
+}
+
+parameter_types! {
+	// Every storage access costs one unit of ref time, so tests can budget `on_idle` work
+	pub const MockDbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 1 };
+}
+
+impl pallet_preimage::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type BaseDeposit = ConstU64<0>;
+	type ByteDeposit = ConstU64<0>;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Weight::MAX;
+}
+
+impl pallet_scheduler::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<u64>;
+	type MaxScheduledPerBlock = ConstU32<16>;
+	type WeightInfo = ();
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub FeeMultiplier: Multiplier = Multiplier::one();
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type OnChargeTransaction = pallet_template::SponsoredTransactionCharge<Test, CurrencyAdapter<Balances, ()>>;
+	type OperationalFeeMultiplier = ConstU8<5>;
+	type WeightToFee = IdentityFee<u64>;
+	type LengthToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
+}
+
 parameter_types! {
 	pub const CoinFlipperPalletId: PalletId = PalletId(*b"coinflip");
 }
 
+// Sponsored calls per account and era, and era length in blocks
+pub const SPONSORED_CALLS_PER_ERA: u32 = 2;
+pub const SPONSORSHIP_ERA: u64 = 10;
+
+// Rate limits can be changed by each test, by default they never kick in
+parameter_types! {
+	pub static MinBlocksBetweenTosses: u64 = 0;
+	pub static MaxTossesPerWindow: u32 = u32::MAX;
+	pub static TossWindow: u64 = 0;
+	// Coins never expire unless a test sets this
+	pub static InactivityPeriod: u64 = 0;
+	// The pot is never swept unless a test sets this
+	pub static SweepPeriod: u64 = 0;
+}
+
+// Operators one coin can have at a time
+pub const MAX_OPERATORS: u32 = 2;
+
+// Active toss schedules one account can have at a time
+pub const MAX_TOSS_SCHEDULES: u32 = 2;
+
+// Tosses in one schedule
+pub const MAX_SCHEDULED_TOSSES: u32 = 10;
+
+// Scheduled tosses are free unless a test sets a fee
+parameter_types! {
+	pub static TossScheduleFee: u64 = 0;
+}
+
+// Initial balance of the pallet pot
+pub const POT_BALANCE: u64 = 1 << 40;
+
+// Account the pot is swept to
+pub const TREASURY: u64 = 1_000;
+
+// Blocks in a day for loss limits, one loss bucket per block
+pub const LOSS_LIMIT_DAY: u64 = 24;
+
+// Blocks after a bankroll deposit before the depositor can withdraw
+pub const BANKROLL_COOLDOWN: u64 = 10;
+
+// Blocks between a bet and the toss settling it
+pub const BET_SETTLEMENT_DELAY: u64 = 2;
+
+// Bets settled in one block
+pub const MAX_BETS_PER_BLOCK: u32 = 2;
+
+parameter_types! {
+	pub const TreasuryAccount: u64 = TREASURY;
+	pub const PotReserveTarget: u64 = POT_BALANCE;
+	pub const HouseEdge: Permill = Permill::from_percent(2);
+}
+
 
 impl pallet_template::Config for Test {
 	type PalletId = CoinFlipperPalletId;
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
 	type MyRandomness = TestRandomness<Self>;
+	type SponsoredCallsPerEra = ConstU32<SPONSORED_CALLS_PER_ERA>;
+	type SponsorshipEra = ConstU64<SPONSORSHIP_ERA>;
+	type MinBlocksBetweenTosses = MinBlocksBetweenTosses;
+	type MaxTossesPerWindow = MaxTossesPerWindow;
+	type TossWindow = TossWindow;
+	type InactivityPeriod = InactivityPeriod;
+	type MaxOperators = ConstU32<MAX_OPERATORS>;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type Preimages = Preimage;
+	type MaxTossSchedules = ConstU32<MAX_TOSS_SCHEDULES>;
+	type MaxScheduledTosses = ConstU32<MAX_SCHEDULED_TOSSES>;
+	type TossScheduleFee = TossScheduleFee;
+	type AdminOrigin = EnsureRoot<u64>;
+	type Currency = Balances;
+	type TreasuryAccount = TreasuryAccount;
+	type PotReserveTarget = PotReserveTarget;
+	type SweepPeriod = SweepPeriod;
+	type BankrollCooldown = ConstU64<BANKROLL_COOLDOWN>;
+	type HouseEdge = HouseEdge;
+	type LossLimitDay = ConstU64<LOSS_LIMIT_DAY>;
+	type BetSettlementDelay = ConstU64<BET_SETTLEMENT_DELAY>;
+	type MaxBetsPerBlock = ConstU32<MAX_BETS_PER_BLOCK>;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
+	let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	
+	// The pot starts funded so that it can sponsor calls
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(TemplateModule::account_id(), POT_BALANCE)] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	
+	// Randomness queued by a previous test on this thread must not leak into this one
+	SCRIPTED_RANDOMNESS.with(|queue| queue.borrow_mut().clear());
+
+	storage.into()
 }
 
 
@@ -66,7 +220,39 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 //
 
 use frame_system::pallet_prelude::BlockNumberFor;
+use std::{cell::RefCell, collections::VecDeque};
+
+thread_local! {
+	// Outputs queued by the current test, served before falling back to hashing the subject
+	static SCRIPTED_RANDOMNESS: RefCell<VecDeque<[u8; 32]>> = RefCell::new(VecDeque::new());
+}
+
+// Queues the output of the next call to the randomness source
+pub fn queue_randomness(output: [u8; 32]) {
+	SCRIPTED_RANDOMNESS.with(|queue| queue.borrow_mut().push_back(output));
+}
+
+// Queues randomness so that the next toss lands on the given side
+pub fn force_next_toss(side: CoinSide) {
+	// The pallet decodes a u32 from the output and lands on Head when it is even
+	let mut output = [0u8; 32];
+	if side == CoinSide::Tail {
+		output[0] = 1;
+	}
+	queue_randomness(output);
+}
+
+// Returns how many queued outputs have not been used yet
+pub fn pending_randomness() -> usize {
+	SCRIPTED_RANDOMNESS.with(|queue| queue.borrow().len())
+}
 
+// Panics if some queued outputs have not been used
+pub fn assert_randomness_consumed() {
+	assert_eq!(pending_randomness(), 0, "queued randomness was not consumed");
+}
+
+// Serves the outputs queued by the test first, then the hash of the subject
 pub struct TestRandomness<T>(frame_support::pallet_prelude::PhantomData<T>);
 
 impl<Output: codec::Decode + Default, T>
@@ -77,9 +263,13 @@ where
 	fn random(subject: &[u8]) -> (Output, BlockNumberFor<T>) {
 		use sp_runtime::traits::TrailingZeroInput;
 
+		// Unscripted outputs hash the subject so that every part of it affects them
+		let output = SCRIPTED_RANDOMNESS
+			.with(|queue| queue.borrow_mut().pop_front())
+			.unwrap_or_else(|| sp_core::hashing::blake2_256(subject));
 		(
-			Output::decode(&mut TrailingZeroInput::new(subject)).unwrap_or_default(),
+			Output::decode(&mut TrailingZeroInput::new(&output)).unwrap_or_default(),
 			frame_system::Pallet::<T>::block_number(),
 		)
 	}
-}
\ No newline at end of file
+}
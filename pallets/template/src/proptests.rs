@@ -0,0 +1,40 @@
+use crate::fuzzing::{run, Action, Op, Origin};
+use proptest::prelude::*;
+
+// Mostly signed origins, so that sequences get past the origin checks
+fn origin() -> impl Strategy<Value = Origin> {
+	prop_oneof![
+		8 => any::<u8>().prop_map(Origin::Signed),
+		1 => Just(Origin::None),
+		1 => Just(Origin::Root),
+	]
+}
+
+fn action() -> impl Strategy<Value = Action> {
+	prop_oneof![Just(Action::Create), Just(Action::Flip), Just(Action::Toss)]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+	prop_oneof![
+		9 => (origin(), action()).prop_map(|(origin, action)| Op::Call { origin, action }),
+		1 => Just(Op::NextBlock),
+	]
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(256))]
+
+	#[test]
+	fn random_call_sequences_keep_invariants_test(ops in prop::collection::vec(op(), 0..64)) {
+		run(&ops);
+	}
+
+	#[test]
+	fn flipping_twice_is_a_no_op_test(who in any::<u8>(), before in prop::collection::vec(op(), 0..16)) {
+		let flip = Op::Call { origin: Origin::Signed(who), action: Action::Flip };
+		let create = Op::Call { origin: Origin::Signed(who), action: Action::Create };
+		let mut ops = before;
+		ops.extend([create, flip, flip]);
+		run(&ops);
+	}
+}
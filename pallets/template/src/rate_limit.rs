@@ -0,0 +1,89 @@
+//! Transaction pool level rate limiting of coin flips and tosses.
+//!
+//! [`CheckTossRate`] applies the same cooldown and per-window cap as the `do_flip`, `do_toss`,
+//! `flip_for` and `toss_for` dispatchables while transactions are validated, so rate limited calls
+//! are rejected by the transaction pool and never make it into a block.
+
+use crate::{Call, Config, Pallet};
+use codec::{Decode, Encode};
+use core::{fmt, marker::PhantomData};
+use frame_support::{
+	sp_runtime::{
+		traits::{DispatchInfoOf, SignedExtension},
+		transaction_validity::{
+			InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+		},
+	},
+	traits::IsSubType,
+};
+use scale_info::TypeInfo;
+
+/// Custom `InvalidTransaction` code returned when an account flips or tosses too often.
+pub const TOO_MANY_TOSSES: u8 = 1;
+
+/// Rejects flips and tosses of accounts that exceed the configured rate limits.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckTossRate<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckTossRate<T> {
+	/// Creates a new instance of the extension.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for CheckTossRate<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> fmt::Debug for CheckTossRate<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckTossRate")
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckTossRate<T>
+where
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "CheckTossRate";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		// Operators are rate limited as the owner of the coin they flip or toss
+		let owner = match call.is_sub_type() {
+			Some(Call::do_flip {} | Call::do_toss {}) => who,
+			Some(Call::flip_for { owner } | Call::toss_for { owner }) => owner,
+			_ => return Ok(ValidTransaction::default()),
+		};
+		Pallet::<T>::ensure_toss_allowed(owner)
+			.map_err(|_| InvalidTransaction::Custom(TOO_MANY_TOSSES))?;
+		Ok(ValidTransaction::default())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+}
@@ -0,0 +1,95 @@
+//! Limits accounts set on their own play.
+//!
+//! An account can pause its play for a while with a cooling-off period, which ends by itself, or
+//! exclude itself, which lasts until the account lifts the exclusion once its term is over. Both
+//! are checked by [`Pallet::ensure_may_play`] on every flip and toss, including those made by
+//! operators and schedules on the account's behalf.
+//!
+//! Losses are recorded in buckets of a 24th of `LossLimitDay` blocks, so the daily and weekly
+//! loss limits apply to a window that rolls forward every bucket. Each bet is checked against
+//! them when it is placed, as if its whole stake were lost, and lost stakes are recorded when the
+//! toss settling the bet lands on the other side.
+
+use crate::{BalanceOf, Config, Error, Pallet, PlayerLimitsOf, RecentLosses};
+use frame_support::{
+	ensure,
+	sp_runtime::{
+		traits::{One, Saturating, Zero},
+		DispatchResult,
+	},
+	traits::Get,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+/// Number of loss buckets per `LossLimitDay`.
+pub const LOSS_BUCKETS_PER_DAY: u32 = 24;
+
+/// Number of loss buckets kept per account, enough to cover a week.
+pub const MAX_LOSS_BUCKETS: u32 = 7 * LOSS_BUCKETS_PER_DAY + 1;
+
+impl<T: Config> Pallet<T> {
+	/// Ensures `who` is neither cooling off nor self-excluded.
+	pub fn ensure_may_play(who: &T::AccountId) -> DispatchResult {
+		let Some(limits) = PlayerLimitsOf::<T>::get(who) else { return Ok(()) };
+		let now = frame_system::Pallet::<T>::block_number();
+
+		ensure!(limits.excluded_until.is_none(), Error::<T>::SelfExcluded);
+		ensure!(!limits.cooling_off_until.is_some_and(|until| now < until), Error::<T>::CoolingOff);
+		Ok(())
+	}
+
+	/// Ensures losing `stake` would keep `who` within its daily and weekly loss limits.
+	pub fn ensure_within_loss_limits(who: &T::AccountId, stake: BalanceOf<T>) -> DispatchResult {
+		let Some(limits) = PlayerLimitsOf::<T>::get(who) else { return Ok(()) };
+		let day = T::LossLimitDay::get();
+
+		if let Some(limit) = limits.daily_loss_limit {
+			let lost = Self::losses_within(who, day);
+			ensure!(lost.saturating_add(stake) <= limit, Error::<T>::LossLimitExceeded);
+		}
+		if let Some(limit) = limits.weekly_loss_limit {
+			let lost = Self::losses_within(who, day.saturating_mul(7u32.into()));
+			ensure!(lost.saturating_add(stake) <= limit, Error::<T>::LossLimitExceeded);
+		}
+		Ok(())
+	}
+
+	/// Records a loss of `amount` by `who` against its loss limits, when a toss settles a lost bet.
+	pub fn note_loss(who: &T::AccountId, amount: BalanceOf<T>) {
+		let now = frame_system::Pallet::<T>::block_number();
+		let bucket_length = Self::loss_bucket_length();
+		let bucket = now.saturating_sub(now % bucket_length);
+		let week = T::LossLimitDay::get().saturating_mul(7u32.into());
+
+		RecentLosses::<T>::mutate(who, |buckets| {
+			buckets.retain(|(start, _)| start.saturating_add(week) > now);
+			match buckets.last_mut() {
+				Some((start, lost)) if *start == bucket => *lost = lost.saturating_add(amount),
+				// Buckets older than a week were just pruned, so this only fails if
+				// `LossLimitDay` shrank. The loss then counts towards the latest bucket
+				_ =>
+					if let Err((_, amount)) = buckets.try_push((bucket, amount)) {
+						if let Some((_, lost)) = buckets.last_mut() {
+							*lost = lost.saturating_add(amount);
+						}
+					},
+			}
+		});
+	}
+
+	/// Returns the losses of `who` in the last `window` blocks, rounded to whole buckets.
+	pub fn losses_within(who: &T::AccountId, window: BlockNumberFor<T>) -> BalanceOf<T> {
+		let now = frame_system::Pallet::<T>::block_number();
+		RecentLosses::<T>::get(who)
+			.iter()
+			.filter(|(start, _)| start.saturating_add(window) > now)
+			.fold(Zero::zero(), |total: BalanceOf<T>, (_, lost)| total.saturating_add(*lost))
+	}
+
+	// Returns the number of blocks in a loss bucket, at least one
+	fn loss_bucket_length() -> BlockNumberFor<T> {
+		let buckets: BlockNumberFor<T> = LOSS_BUCKETS_PER_DAY.into();
+		let length = T::LossLimitDay::get().saturating_add(buckets - One::one()) / buckets;
+		length.max(One::one())
+	}
+}
@@ -0,0 +1,59 @@
+//! Bet size limits relative to the bankroll.
+//!
+//! The house wins an even-money bet with probability `(1 + edge) / 2`, so the Kelly criterion
+//! stakes at most `edge` of the bankroll on a single bet. [`kelly_max_bet`] applies that fraction
+//! to the part of the pot that is not already committed to outstanding bets, and
+//! [`Pallet::max_bet`] exposes the current limit to the runtime API.
+//!
+//! The tosses themselves are fair, so the house takes its edge from the payouts: a won bet pays
+//! out twice its stake less the edge, see [`Pallet::payout_for`].
+
+use crate::{BalanceOf, Config, Error, Pallet, TotalExposure};
+use codec::Codec;
+use frame_support::{
+	ensure,
+	sp_runtime::{
+		traits::{AtLeast32BitUnsigned, Saturating},
+		DispatchResult, Permill,
+	},
+	traits::{Currency, Get},
+};
+
+/// Returns the largest bet a bankroll can take at the given house edge, once `exposure` is set
+/// aside to pay out the bets that are still open.
+pub fn kelly_max_bet<Balance: AtLeast32BitUnsigned + Copy>(
+	bankroll: Balance,
+	house_edge: Permill,
+	exposure: Balance,
+) -> Balance {
+	house_edge.mul_floor(bankroll.saturating_sub(exposure))
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the largest bet the pot can take at the current pot balance, once the payouts of
+	/// all open bets are set aside.
+	pub fn max_bet() -> BalanceOf<T> {
+		let bankroll = T::Currency::free_balance(&Self::account_id());
+		kelly_max_bet(bankroll, T::HouseEdge::get(), TotalExposure::<T>::get())
+	}
+
+	/// Returns what a won bet of `stake` pays out, stake included.
+	pub fn payout_for(stake: BalanceOf<T>) -> BalanceOf<T> {
+		let doubled = stake.saturating_add(stake);
+		doubled.saturating_sub(T::HouseEdge::get().mul_ceil(doubled))
+	}
+
+	/// Ensures a bet of `stake` is within [`Self::max_bet`].
+	pub fn ensure_within_risk_limit(stake: BalanceOf<T>) -> DispatchResult {
+		ensure!(stake <= Self::max_bet(), Error::<T>::BetExceedsRiskLimit);
+		Ok(())
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Exposes the bet size limits of the coin-flipper pot.
+	pub trait CoinFlipperRiskApi<Balance> where Balance: Codec {
+		/// Returns the largest bet the pot can take right now.
+		fn max_bet() -> Balance;
+	}
+}
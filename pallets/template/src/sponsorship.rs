@@ -0,0 +1,89 @@
+//! Transaction fee sponsorship for coin-flipper calls.
+//!
+//! [`SponsoredTransactionCharge`] wraps the runtime's `OnChargeTransaction` implementation so
+//! that the fees of `do_toss` and `do_flip` are paid by the pallet pot, as long as the caller
+//! owns a coin and has not used up its quota of sponsored calls for the current era. Creating the
+//! coin is never sponsored, so every new account pays for its coin before the pot pays for any of
+//! its calls. Every other call, and every call beyond the quota, is charged to the sender as usual.
+//!
+//! Once the pot has liquidity providers, sponsored fees are paid out of the house's stake only:
+//! the house shares worth each fee are burnt, so bankroll shares keep their value. The house's
+//! stake leaves out the payouts set aside for open bets, so fees are no longer sponsored once
+//! the pot cannot cover them on top of those payouts.
+
+use crate::{Call, Config, Pallet};
+use core::marker::PhantomData;
+use frame_support::{
+	sp_runtime::{
+		traits::{DispatchInfoOf, PostDispatchInfoOf, SaturatedConversion, Zero},
+		transaction_validity::TransactionValidityError,
+	},
+	traits::IsSubType,
+};
+use pallet_transaction_payment::OnChargeTransaction;
+
+type RuntimeCallOf<T> = <T as frame_system::Config>::RuntimeCall;
+
+/// Charges the pot instead of the sender for sponsored coin-flipper calls.
+///
+/// `OCT` is the fee handler that actually moves the funds, e.g. `CurrencyAdapter`.
+pub struct SponsoredTransactionCharge<T, OCT>(PhantomData<(T, OCT)>);
+
+impl<T, OCT> OnChargeTransaction<T> for SponsoredTransactionCharge<T, OCT>
+where
+	T: Config + pallet_transaction_payment::Config,
+	RuntimeCallOf<T>: IsSubType<Call<T>>,
+	OCT: OnChargeTransaction<T>,
+{
+	type Balance = OCT::Balance;
+	// The account that paid the fee, if it was not the sender, and the inner liquidity info.
+	type LiquidityInfo = (Option<T::AccountId>, OCT::LiquidityInfo);
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &RuntimeCallOf<T>,
+		dispatch_info: &DispatchInfoOf<RuntimeCallOf<T>>,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		// The pot never pays tips, otherwise anyone could drain it. The house's stake leaves out
+		// the payouts of open bets, so fees are never paid with them.
+		if tip.is_zero() &&
+			Pallet::<T>::is_sponsorable(who, call) &&
+			Pallet::<T>::house_stake() >= fee.saturated_into::<u128>().saturated_into()
+		{
+			let pot = Pallet::<T>::account_id();
+			// If the pot cannot pay, fall back to charging the sender.
+			if let Ok(liquidity) = OCT::withdraw_fee(&pot, call, dispatch_info, fee, tip) {
+				Pallet::<T>::note_sponsored_call(who);
+				return Ok((Some(pot), liquidity))
+			}
+		}
+
+		OCT::withdraw_fee(who, call, dispatch_info, fee, tip).map(|liquidity| (None, liquidity))
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		dispatch_info: &DispatchInfoOf<RuntimeCallOf<T>>,
+		post_info: &PostDispatchInfoOf<RuntimeCallOf<T>>,
+		corrected_fee: Self::Balance,
+		tip: Self::Balance,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		// Refunds go back to whoever paid the fee.
+		let (payer, liquidity) = already_withdrawn;
+		OCT::correct_and_deposit_fee(
+			payer.as_ref().unwrap_or(who),
+			dispatch_info,
+			post_info,
+			corrected_fee,
+			tip,
+			liquidity,
+		)?;
+		if payer.is_some() {
+			Pallet::<T>::charge_house(corrected_fee.saturated_into::<u128>().saturated_into());
+		}
+		Ok(())
+	}
+}
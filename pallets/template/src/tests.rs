@@ -1,10 +1,42 @@
-use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok};
-use sp_runtime::traits::BadOrigin;
+use crate::{
+	mock::*, rate_limit::TOO_MANY_TOSSES, risk::kelly_max_bet, weights::WeightInfo, BankrollShares,
+	BetsDue, CheckTossRate, CoinLastActive, CoinOperators, CoinSide, CoinStorage, Error, Event,
+	ExpiryCursor, NextTossScheduleId, OpenBets, OperatorApproval, PlayerLimitsOf, SponsoredCalls,
+	SponsorshipQuota, TossActivityOf, TossNonce, TossSchedules, TotalBankrollShares, TotalExposure,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	traits::{Hooks, OnKilledAccount},
+	weights::Weight,
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::{
+	traits::{BadOrigin, Dispatchable, SignedExtension},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	BuildStorage, Permill,
+};
 
 type SignedOrigin = u64;
 
 const ALICE: SignedOrigin = 1u64;
+const BOB: SignedOrigin = 2u64;
+const CHARLIE: SignedOrigin = 3u64;
+
+// Charges the fees of a call the way the transaction payment extension does before dispatch
+fn charge_fees(who: SignedOrigin, call: crate::Call<Test>, tip: u64) -> Result<(), TransactionValidityError> {
+	let call = RuntimeCall::TemplateModule(call);
+	let info = call.get_dispatch_info();
+	ChargeTransactionPayment::<Test>::from(tip).pre_dispatch(&who, &call, &info, 0).map(|_| ())
+}
+
+// Charges the fees of a call like `charge_fees`, then settles them as after a successful dispatch
+fn pay_fees(who: SignedOrigin, call: crate::Call<Test>) -> Result<(), TransactionValidityError> {
+	let call = RuntimeCall::TemplateModule(call);
+	let info = call.get_dispatch_info();
+	let pre = ChargeTransactionPayment::<Test>::from(0).pre_dispatch(&who, &call, &info, 0)?;
+	ChargeTransactionPayment::<Test>::post_dispatch(Some(pre), &info, &PostDispatchInfo::default(), 0, &Ok(()))
+}
 
 #[test]
 fn create_coin_test() {
@@ -66,7 +98,7 @@ fn flip_coin_test() {
 		let result = TemplateModule::do_flip(origin);
 		assert_ok!(result);
 
-		System::assert_has_event(Event::CoinFlipped { who: ALICE }.into());
+		System::assert_has_event(Event::CoinFlipped { who: ALICE, side: CoinSide::Tail }.into());
 	});
 }
 
@@ -104,10 +136,13 @@ fn toss_coin_test() {
 		let result = TemplateModule::create_coin(origin.clone());
 		assert_ok!(result);
 
+		force_next_toss(CoinSide::Tail);
 		let result = TemplateModule::do_toss(origin);
 		assert_ok!(result);
 
-		System::assert_has_event(Event::CoinTossed { who: ALICE }.into());
+		assert_eq!(CoinStorage::<Test>::get(ALICE).unwrap().side(), &CoinSide::Tail);
+		System::assert_has_event(Event::CoinTossed { who: ALICE, side: CoinSide::Tail, nonce: 0 }.into());
+		assert_randomness_consumed();
 	});
 }
 
@@ -149,7 +184,7 @@ fn create_coin_and_flip_coin_test() {
 		assert_ok!(result);
 
 		System::assert_has_event(Event::CoinCreated { who: ALICE }.into());
-		System::assert_has_event(Event::CoinFlipped { who: ALICE }.into());
+		System::assert_has_event(Event::CoinFlipped { who: ALICE, side: CoinSide::Tail }.into());
 	});
 }
 
@@ -167,8 +202,9 @@ fn create_coin_and_toss_coin_test() {
 		let result = TemplateModule::do_toss(origin);
 		assert_ok!(result);
 
+		let side = CoinStorage::<Test>::get(ALICE).unwrap().side().clone();
 		System::assert_has_event(Event::CoinCreated { who: ALICE }.into());
-		System::assert_has_event(Event::CoinTossed { who: ALICE }.into());
+		System::assert_has_event(Event::CoinTossed { who: ALICE, side, nonce: 0 }.into());
 	});
 }
 
@@ -189,8 +225,1316 @@ fn create_coin_and_flip_coin_and_toss_coin_test() {
 		let result = TemplateModule::do_toss(origin);
 		assert_ok!(result);
 
+		let side = CoinStorage::<Test>::get(ALICE).unwrap().side().clone();
 		System::assert_has_event(Event::CoinCreated { who: ALICE }.into());
-		System::assert_has_event(Event::CoinFlipped { who: ALICE }.into());
-		System::assert_has_event(Event::CoinTossed { who: ALICE }.into());
+		System::assert_has_event(Event::CoinFlipped { who: ALICE, side: CoinSide::Tail }.into());
+		System::assert_has_event(Event::CoinTossed { who: ALICE, side, nonce: 0 }.into());
+	});
+}
+
+#[test]
+fn sponsored_toss_is_paid_by_the_pot_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		// ALICE holds no funds, the pot pays for the toss
+		assert_ok!(charge_fees(ALICE, crate::Call::do_toss {}, 0));
+
+		assert_eq!(Balances::free_balance(ALICE), 0);
+		assert!(Balances::free_balance(TemplateModule::account_id()) < POT_BALANCE);
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), SPONSORED_CALLS_PER_ERA - 1);
+	});
+}
+
+#[test]
+fn sponsored_calls_are_limited_per_era_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		assert_ok!(charge_fees(ALICE, crate::Call::do_flip {}, 0));
+		assert_ok!(charge_fees(ALICE, crate::Call::do_toss {}, 0));
+
+		// The quota is used up and ALICE has no funds to pay the fees
+		assert_eq!(
+			charge_fees(ALICE, crate::Call::do_toss {}, 0),
+			Err(InvalidTransaction::Payment.into())
+		);
+
+		// A new era starts with a fresh quota
+		System::set_block_number(SPONSORSHIP_ERA + 1);
+		assert_ok!(charge_fees(ALICE, crate::Call::do_toss {}, 0));
+	});
+}
+
+#[test]
+fn coin_creation_is_not_sponsored_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		// New accounts pay for their coin, so making accounts does not get more sponsored calls
+		for who in [ALICE, BOB] {
+			assert_eq!(
+				charge_fees(who, crate::Call::create_coin {}, 0),
+				Err(InvalidTransaction::Payment.into())
+			);
+			assert_eq!(TemplateModule::sponsored_calls_left(&who), SPONSORED_CALLS_PER_ERA);
+		}
+		assert_eq!(Balances::free_balance(TemplateModule::account_id()), POT_BALANCE);
+
+		// Only once the coin exists are its tosses sponsored
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(charge_fees(ALICE, crate::Call::do_toss {}, 0));
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), SPONSORED_CALLS_PER_ERA - 1);
+	});
+}
+
+#[test]
+fn toss_without_a_coin_is_not_sponsored_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		assert_eq!(
+			charge_fees(ALICE, crate::Call::do_toss {}, 0),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(Balances::free_balance(TemplateModule::account_id()), POT_BALANCE);
+	});
+}
+
+#[test]
+fn toss_with_a_tip_is_not_sponsored_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		assert_eq!(
+			charge_fees(ALICE, crate::Call::do_toss {}, 1),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), SPONSORED_CALLS_PER_ERA);
+	});
+}
+
+#[test]
+fn toss_during_cooldown_test() {
+	new_test_ext().execute_with(|| {
+		
+		MinBlocksBetweenTosses::set(3);
+		System::set_block_number(1);
+		
+		let origin = RuntimeOrigin::signed(ALICE);
+
+		assert_ok!(TemplateModule::create_coin(origin.clone()));
+		assert_ok!(TemplateModule::do_toss(origin.clone()));
+
+		// Flips and tosses share the same cooldown
+		System::set_block_number(3);
+		assert_noop!(TemplateModule::do_flip(origin.clone()), Error::<Test>::TooManyTosses);
+		assert_noop!(TemplateModule::do_toss(origin.clone()), Error::<Test>::TooManyTosses);
+
+		System::set_block_number(4);
+		assert_ok!(TemplateModule::do_toss(origin));
+	});
+}
+
+#[test]
+fn toss_above_window_cap_test() {
+	new_test_ext().execute_with(|| {
+		
+		MaxTossesPerWindow::set(2);
+		TossWindow::set(10);
+		System::set_block_number(1);
+		
+		let origin = RuntimeOrigin::signed(ALICE);
+
+		assert_ok!(TemplateModule::create_coin(origin.clone()));
+		assert_ok!(TemplateModule::do_toss(origin.clone()));
+		assert_ok!(TemplateModule::do_flip(origin.clone()));
+		assert_noop!(TemplateModule::do_toss(origin.clone()), Error::<Test>::TooManyTosses);
+
+		// Other accounts are not affected
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(BOB)));
+		assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(BOB)));
+
+		// The cap is reset when the window is over
+		System::set_block_number(11);
+		assert_ok!(TemplateModule::do_toss(origin));
+	});
+}
+
+#[test]
+fn rate_limited_toss_is_invalid_transaction_test() {
+	new_test_ext().execute_with(|| {
+		
+		MinBlocksBetweenTosses::set(3);
+		System::set_block_number(1);
+		
+		let origin = RuntimeOrigin::signed(ALICE);
+		let call = RuntimeCall::TemplateModule(crate::Call::do_toss {});
+		let info = call.get_dispatch_info();
+
+		assert_ok!(TemplateModule::create_coin(origin.clone()));
+		assert_ok!(CheckTossRate::<Test>::new().validate(&ALICE, &call, &info, 0));
+		assert_ok!(TemplateModule::do_toss(origin));
+
+		assert_eq!(
+			CheckTossRate::<Test>::new().validate(&ALICE, &call, &info, 0),
+			Err(InvalidTransaction::Custom(TOO_MANY_TOSSES).into())
+		);
+
+		// Other calls are never rate limited
+		let call = RuntimeCall::TemplateModule(crate::Call::create_coin {});
+		assert_ok!(CheckTossRate::<Test>::new().validate(&ALICE, &call, &info, 0));
+	});
+}
+
+#[test]
+fn genesis_coins_test() {
+	let storage = crate::GenesisConfig::<Test> { coins: vec![ALICE] }.build_storage().unwrap();
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		
+		assert_eq!(CoinStorage::<Test>::get(ALICE).unwrap().side(), &CoinSide::Head);
+		assert!(CoinStorage::<Test>::get(BOB).is_none());
+	});
+}
+
+// Returns the side and nonce of every toss in the current block
+fn tosses() -> Vec<(SignedOrigin, CoinSide, u32)> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::TemplateModule(Event::CoinTossed { who, side, nonce }) => Some((who, side, nonce)),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn tosses_of_different_accounts_in_one_block_can_diverge_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		for who in 1..=16 {
+			assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(who)));
+			assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(who)));
+		}
+
+		let tosses = tosses();
+		assert_eq!(tosses.len(), 16);
+		assert!(tosses.iter().any(|(_, side, _)| *side == CoinSide::Head));
+		assert!(tosses.iter().any(|(_, side, _)| *side == CoinSide::Tail));
+	});
+}
+
+#[test]
+fn tosses_of_one_account_in_one_block_can_diverge_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		let origin = RuntimeOrigin::signed(ALICE);
+
+		assert_ok!(TemplateModule::create_coin(origin.clone()));
+		for _ in 0..16 {
+			assert_ok!(TemplateModule::do_toss(origin.clone()));
+		}
+
+		// Every toss used the next nonce
+		let tosses = tosses();
+		let nonces: Vec<u32> = tosses.iter().map(|(_, _, nonce)| *nonce).collect();
+		assert_eq!(nonces, (0..16).collect::<Vec<u32>>());
+		assert_eq!(TossNonce::<Test>::get(ALICE), 16);
+
+		assert!(tosses.iter().any(|(_, side, _)| *side == CoinSide::Head));
+		assert!(tosses.iter().any(|(_, side, _)| *side == CoinSide::Tail));
+	});
+}
+
+#[test]
+fn toss_is_reproducible_from_its_event_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		for who in [ALICE, BOB] {
+			assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(who)));
+			assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(who)));
+			assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(who)));
+		}
+
+		for (who, side, nonce) in tosses() {
+			assert_eq!(TemplateModule::toss_outcome(&who, nonce), side);
+		}
+	});
+}
+
+#[test]
+fn forced_tosses_land_on_the_scripted_sides_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		let origin = RuntimeOrigin::signed(ALICE);
+		assert_ok!(TemplateModule::create_coin(origin.clone()));
+
+		let sides = [CoinSide::Tail, CoinSide::Tail, CoinSide::Head, CoinSide::Tail];
+		for side in sides.clone() {
+			force_next_toss(side);
+		}
+		for _ in 0..sides.len() {
+			assert_ok!(TemplateModule::do_toss(origin.clone()));
+		}
+
+		let tossed: Vec<CoinSide> = tosses().into_iter().map(|(_, side, _)| side).collect();
+		assert_eq!(tossed, sides);
+		assert_eq!(CoinStorage::<Test>::get(ALICE).unwrap().side(), &CoinSide::Tail);
+		assert_randomness_consumed();
+	});
+}
+
+#[test]
+fn only_successful_tosses_consume_randomness_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		let origin = RuntimeOrigin::signed(ALICE);
+		force_next_toss(CoinSide::Tail);
+
+		// Tossing without a coin and flipping leave the scripted output in place
+		assert_noop!(TemplateModule::do_toss(origin.clone()), Error::<Test>::CoinDoesNotExist);
+		assert_ok!(TemplateModule::create_coin(origin.clone()));
+		assert_ok!(TemplateModule::do_flip(origin.clone()));
+		assert_eq!(pending_randomness(), 1);
+
+		assert_ok!(TemplateModule::do_toss(origin));
+		System::assert_last_event(Event::CoinTossed { who: ALICE, side: CoinSide::Tail, nonce: 0 }.into());
+		assert_randomness_consumed();
+	});
+}
+
+#[test]
+fn coin_events_are_indexed_by_account_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(BOB)));
+		assert_ok!(TemplateModule::do_flip(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)));
+
+		let topic = TemplateModule::account_topic(&ALICE);
+		let indexed: Vec<RuntimeEvent> = System::event_topics(&topic)
+			.into_iter()
+			.map(|(block, index)| {
+				assert_eq!(block, 1);
+				System::events()[index as usize].event.clone()
+			})
+			.collect();
+
+		let side = CoinStorage::<Test>::get(ALICE).unwrap().side().clone();
+		assert_eq!(
+			indexed,
+			vec![
+				Event::CoinCreated { who: ALICE }.into(),
+				Event::CoinFlipped { who: ALICE, side: CoinSide::Tail }.into(),
+				Event::CoinTossed { who: ALICE, side, nonce: 0 }.into(),
+			]
+		);
+		assert_eq!(System::event_topics(&TemplateModule::account_topic(&BOB)).len(), 1);
+	});
+}
+
+#[test]
+fn reaped_account_loses_its_coin_test() {
+	new_test_ext().execute_with(|| {
+		
+		System::set_block_number(1);
+		
+		Balances::make_free_balance_be(&ALICE, 1_000);
+		Balances::make_free_balance_be(&BOB, 1_000);
+		for who in [ALICE, BOB] {
+			assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(who)));
+			assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(who)));
+		}
+		assert_ok!(charge_fees(ALICE, crate::Call::do_toss {}, 0));
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None));
+
+		// Sending away the whole balance reaps the account
+		assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(ALICE), BOB, false));
+		assert!(!System::account_exists(&ALICE));
+
+		assert!(CoinStorage::<Test>::get(ALICE).is_none());
+		assert_eq!(TossNonce::<Test>::get(ALICE), 0);
+		assert!(!SponsoredCalls::<Test>::contains_key(ALICE));
+		assert!(TossActivityOf::<Test>::get(ALICE).is_none());
+		assert!(!CoinOperators::<Test>::contains_key(ALICE));
+		System::assert_has_event(Event::CoinRemoved { who: ALICE }.into());
+
+		// Other accounts keep their coin
+		assert!(CoinStorage::<Test>::get(BOB).is_some());
+		assert_eq!(TossNonce::<Test>::get(BOB), 1);
+	});
+}
+
+// Returns the accounts whose coin expired in the current block
+fn expired() -> Vec<u64> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::TemplateModule(Event::CoinExpired { who }) => Some(who),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn inactive_coins_expire_on_idle_test() {
+	new_test_ext().execute_with(|| {
+		InactivityPeriod::set(10);
+
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(BOB)));
+		assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(BOB)));
+
+		System::set_block_number(8);
+		assert_ok!(TemplateModule::do_flip(RuntimeOrigin::signed(BOB)));
+
+		// Idle for exactly the inactivity period is not enough
+		System::set_block_number(11);
+		TemplateModule::on_idle(11, Weight::MAX);
+		assert!(CoinStorage::<Test>::get(ALICE).is_some());
+
+		System::set_block_number(12);
+		TemplateModule::on_idle(12, Weight::MAX);
+		assert!(CoinStorage::<Test>::get(ALICE).is_none());
+		assert!(!CoinLastActive::<Test>::contains_key(ALICE));
+		assert_eq!(expired(), vec![ALICE]);
+
+		// Bob flipped at block 8, so his coin and toss nonce are kept
+		assert!(CoinStorage::<Test>::get(BOB).is_some());
+		assert_eq!(TossNonce::<Test>::get(BOB), 1);
+		assert_eq!(CoinLastActive::<Test>::get(BOB), 8);
+	});
+}
+
+#[test]
+fn coin_expiry_resumes_where_it_stopped_test() {
+	new_test_ext().execute_with(|| {
+		InactivityPeriod::set(10);
+
+		System::set_block_number(1);
+		for who in 1..=5 {
+			assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(who)));
+		}
+		System::set_block_number(20);
+
+		// Weight for the cursor and two coins, each storage access costs one unit in the mock
+		let base = Weight::from_parts(2, 0);
+		let per_coin = Weight::from_parts(21, 0);
+		let budget = base + per_coin * 2;
+
+		assert_eq!(TemplateModule::on_idle(20, budget), budget);
+		assert_eq!(expired().len(), 2);
+		assert!(ExpiryCursor::<Test>::get().is_some());
+
+		assert_eq!(TemplateModule::on_idle(20, budget), budget);
+		assert_eq!(expired().len(), 4);
+
+		// The last coin is visited, then the cursor starts over
+		assert_eq!(TemplateModule::on_idle(20, budget), base + per_coin);
+		assert!(ExpiryCursor::<Test>::get().is_none());
+
+		let mut expired = expired();
+		expired.sort();
+		assert_eq!(expired, vec![1, 2, 3, 4, 5]);
+		assert_eq!(CoinStorage::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn coin_expiry_needs_weight_and_a_period_test() {
+	new_test_ext().execute_with(|| {
+
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		System::set_block_number(1_000);
+
+		// Expiry is disabled by default in the mock
+		assert_eq!(TemplateModule::on_idle(1_000, Weight::MAX), Weight::zero());
+
+		// Not enough weight left for a single coin
+		InactivityPeriod::set(10);
+		assert_eq!(TemplateModule::on_idle(1_000, Weight::from_parts(8, 0)), Weight::zero());
+		assert!(CoinStorage::<Test>::get(ALICE).is_some());
+
+		TemplateModule::on_idle(1_000, Weight::MAX);
+		assert!(CoinStorage::<Test>::get(ALICE).is_none());
+	});
+}
+
+#[test]
+fn operator_flips_and_tosses_for_owner_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		// Not approved yet
+		assert_noop!(
+			TemplateModule::flip_for(RuntimeOrigin::signed(BOB), ALICE),
+			Error::<Test>::NotAnOperator
+		);
+
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None));
+		System::assert_last_event(Event::OperatorApproved { owner: ALICE, operator: BOB, expiry: None }.into());
+
+		assert_ok!(TemplateModule::flip_for(RuntimeOrigin::signed(BOB), ALICE));
+		assert_eq!(CoinStorage::<Test>::get(ALICE).unwrap().side(), &CoinSide::Tail);
+		System::assert_last_event(Event::CoinFlipped { who: ALICE, side: CoinSide::Tail }.into());
+
+		force_next_toss(CoinSide::Head);
+		assert_ok!(TemplateModule::toss_for(RuntimeOrigin::signed(BOB), ALICE));
+		assert_eq!(CoinStorage::<Test>::get(ALICE).unwrap().side(), &CoinSide::Head);
+		System::assert_last_event(Event::CoinTossed { who: ALICE, side: CoinSide::Head, nonce: 0 }.into());
+		assert_eq!(TossNonce::<Test>::get(ALICE), 1);
+
+		// The operator has no coin of its own, and other owners did not approve it
+		assert!(CoinStorage::<Test>::get(BOB).is_none());
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(CHARLIE)));
+		assert_noop!(
+			TemplateModule::toss_for(RuntimeOrigin::signed(BOB), CHARLIE),
+			Error::<Test>::NotAnOperator
+		);
+	});
+}
+
+#[test]
+fn operator_approvals_expire_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		assert_noop!(
+			TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, Some(4)),
+			Error::<Test>::ApprovalExpired
+		);
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, Some(6)));
+
+		// The expiry block is the last one in which the operator may act
+		System::set_block_number(6);
+		assert_ok!(TemplateModule::flip_for(RuntimeOrigin::signed(BOB), ALICE));
+
+		System::set_block_number(7);
+		assert_noop!(
+			TemplateModule::flip_for(RuntimeOrigin::signed(BOB), ALICE),
+			Error::<Test>::NotAnOperator
+		);
+
+		// Approving again replaces the expiry
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None));
+		assert_eq!(
+			CoinOperators::<Test>::get(ALICE).into_inner(),
+			vec![OperatorApproval { operator: BOB, expiry: None }]
+		);
+		assert_ok!(TemplateModule::flip_for(RuntimeOrigin::signed(BOB), ALICE));
+	});
+}
+
+#[test]
+fn operator_approvals_are_bounded_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None),
+			Error::<Test>::CoinDoesNotExist
+		);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		for operator in 10..10 + MAX_OPERATORS as u64 {
+			assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), operator, Some(1)));
+		}
+		assert_noop!(
+			TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None),
+			Error::<Test>::TooManyOperators
+		);
+
+		// Expired approvals make room for new ones
+		System::set_block_number(2);
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None));
+		assert_eq!(CoinOperators::<Test>::get(ALICE).len(), 1);
+	});
+}
+
+#[test]
+fn revoked_operator_cannot_act_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None));
+
+		assert_ok!(TemplateModule::revoke_operator(RuntimeOrigin::signed(ALICE), BOB));
+		System::assert_last_event(Event::OperatorRevoked { owner: ALICE, operator: BOB }.into());
+		assert!(!CoinOperators::<Test>::contains_key(ALICE));
+
+		assert_noop!(
+			TemplateModule::toss_for(RuntimeOrigin::signed(BOB), ALICE),
+			Error::<Test>::NotAnOperator
+		);
+		assert_noop!(
+			TemplateModule::revoke_operator(RuntimeOrigin::signed(ALICE), BOB),
+			Error::<Test>::NotAnOperator
+		);
+	});
+}
+
+#[test]
+fn operators_share_the_owner_rate_limit_test() {
+	new_test_ext().execute_with(|| {
+		MinBlocksBetweenTosses::set(3);
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None));
+		assert_ok!(TemplateModule::do_flip(RuntimeOrigin::signed(ALICE)));
+
+		assert_noop!(
+			TemplateModule::flip_for(RuntimeOrigin::signed(BOB), ALICE),
+			Error::<Test>::TooManyTosses
+		);
+		let call = RuntimeCall::TemplateModule(crate::Call::toss_for { owner: ALICE });
+		let info = call.get_dispatch_info();
+		assert_eq!(
+			CheckTossRate::<Test>::new().validate(&BOB, &call, &info, 0),
+			Err(InvalidTransaction::Custom(TOO_MANY_TOSSES).into())
+		);
+
+		System::set_block_number(4);
+		assert_ok!(CheckTossRate::<Test>::new().validate(&BOB, &call, &info, 0));
+		assert_ok!(TemplateModule::toss_for(RuntimeOrigin::signed(BOB), ALICE));
+	});
+}
+
+// Moves on to block `n`, dispatching the calls scheduled up to it
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		let next = System::block_number() + 1;
+		System::set_block_number(next);
+		Scheduler::on_initialize(next);
+	}
+}
+
+#[test]
+fn scheduled_tosses_are_dispatched_periodically_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 3, 2));
+		System::assert_last_event(Event::TossesScheduled { who: ALICE, id: 0, every: 3, count: 2 }.into());
+
+		// Tossed at blocks 4 and 7, on Alice's behalf
+		run_to_block(3);
+		assert_eq!(TossNonce::<Test>::get(ALICE), 0);
+		force_next_toss(CoinSide::Tail);
+		run_to_block(4);
+		assert_eq!(tosses(), vec![(ALICE, CoinSide::Tail, 0)]);
+		force_next_toss(CoinSide::Head);
+		run_to_block(7);
+		assert_eq!(tosses(), vec![(ALICE, CoinSide::Tail, 0), (ALICE, CoinSide::Head, 1)]);
+		assert_randomness_consumed();
+
+		run_to_block(20);
+		assert_eq!(TossNonce::<Test>::get(ALICE), 2);
+
+		// The schedule ran out, so it cannot be cancelled any more
+		assert_noop!(
+			TemplateModule::cancel_toss_schedule(RuntimeOrigin::signed(ALICE), 0),
+			Error::<Test>::TossScheduleNotFound
+		);
+	});
+}
+
+#[test]
+fn cancelled_toss_schedule_stops_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, 10));
+
+		run_to_block(5);
+		assert_eq!(TossNonce::<Test>::get(ALICE), 2);
+
+		assert_ok!(TemplateModule::cancel_toss_schedule(RuntimeOrigin::signed(ALICE), 0));
+		System::assert_last_event(Event::TossScheduleCancelled { who: ALICE, id: 0 }.into());
+		assert!(!TossSchedules::<Test>::contains_key(ALICE));
+
+		run_to_block(30);
+		assert_eq!(TossNonce::<Test>::get(ALICE), 2);
+
+		// Other accounts cannot cancel schedules they do not own
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, 10));
+		assert_noop!(
+			TemplateModule::cancel_toss_schedule(RuntimeOrigin::signed(BOB), 1),
+			Error::<Test>::TossScheduleNotFound
+		);
+	});
+}
+
+#[test]
+fn toss_schedules_are_bounded_per_account_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, 1),
+			Error::<Test>::CoinDoesNotExist
+		);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		for _ in 0..MAX_TOSS_SCHEDULES {
+			assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, 1));
+		}
+		assert_noop!(
+			TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, 1),
+			Error::<Test>::TooManyTossSchedules
+		);
+
+		// Schedules that ran out make room for new ones
+		run_to_block(3);
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, 1));
+		assert_eq!(TossSchedules::<Test>::get(ALICE).len(), 1);
+		assert_eq!(NextTossScheduleId::<Test>::get(ALICE), MAX_TOSS_SCHEDULES + 1);
+	});
+}
+
+#[test]
+fn toss_schedules_respect_rate_limits_test() {
+	new_test_ext().execute_with(|| {
+		MinBlocksBetweenTosses::set(3);
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		for (every, count) in [(0, 5), (2, 5), (3, 0)] {
+			assert_noop!(
+				TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), every, count),
+				Error::<Test>::InvalidTossSchedule
+			);
+		}
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 3, 5));
+	});
+}
+
+#[test]
+fn toss_schedules_fit_in_the_toss_window_test() {
+	new_test_ext().execute_with(|| {
+		MaxTossesPerWindow::set(2);
+		TossWindow::set(10);
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		// A third toss 8 blocks after the first would land in the same window
+		assert_noop!(
+			TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 4, 3),
+			Error::<Test>::InvalidTossSchedule
+		);
+
+		// Any interval fits as long as the schedule does not exceed the cap, and every toss of a
+		// schedule that fits is dispatched
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 1, 2));
+		assert_ok!(TemplateModule::cancel_toss_schedule(RuntimeOrigin::signed(ALICE), 0));
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 5, 3));
+		run_to_block(20);
+		assert_eq!(TossNonce::<Test>::get(ALICE), 3);
+	});
+}
+
+#[test]
+fn toss_schedules_are_capped_and_paid_up_front_test() {
+	new_test_ext().execute_with(|| {
+		TossScheduleFee::set(5);
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&ALICE, 100);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(BOB)));
+
+		assert_noop!(
+			TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, MAX_SCHEDULED_TOSSES + 1),
+			Error::<Test>::InvalidTossSchedule
+		);
+		assert_noop!(
+			TemplateModule::schedule_tosses(RuntimeOrigin::signed(BOB), 2, 1),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+
+		// The whole schedule is paid for when it is created, and cancelling it refunds nothing
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, MAX_SCHEDULED_TOSSES));
+		assert_eq!(Balances::free_balance(ALICE), 50);
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE + 50);
+		assert_ok!(TemplateModule::cancel_toss_schedule(RuntimeOrigin::signed(ALICE), 0));
+		assert_eq!(Balances::free_balance(ALICE), 50);
+	});
+}
+
+#[test]
+fn removed_coin_cancels_its_toss_schedules_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 2, 10));
+
+		TemplateModule::on_killed_account(&ALICE);
+		assert!(!TossSchedules::<Test>::contains_key(ALICE));
+		assert!(!NextTossScheduleId::<Test>::contains_key(ALICE));
+
+		// A new coin is not tossed by the schedules of the old one
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		run_to_block(30);
+		assert_eq!(TossNonce::<Test>::get(ALICE), 0);
+	});
+}
+
+#[test]
+fn admin_sets_the_sponsorship_quota_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), SPONSORED_CALLS_PER_ERA);
+
+		assert_noop!(TemplateModule::set_sponsorship_quota(RuntimeOrigin::signed(ALICE), Some(5)), BadOrigin);
+
+		assert_ok!(TemplateModule::set_sponsorship_quota(RuntimeOrigin::root(), Some(5)));
+		System::assert_last_event(Event::SponsorshipQuotaSet { calls: Some(5) }.into());
+		assert_eq!(SponsorshipQuota::<Test>::get(), Some(5));
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), 5);
+
+		// Calls already sponsored count against the new quota
+		assert_ok!(charge_fees(ALICE, crate::Call::do_toss {}, 0));
+		assert_ok!(TemplateModule::set_sponsorship_quota(RuntimeOrigin::root(), Some(1)));
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), 0);
+
+		assert_ok!(TemplateModule::set_sponsorship_quota(RuntimeOrigin::root(), None));
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), SPONSORED_CALLS_PER_ERA - 1);
+	});
+}
+
+#[test]
+fn pot_is_swept_to_the_treasury_periodically_test() {
+	new_test_ext().execute_with(|| {
+		SweepPeriod::set(10);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&pot, POT_BALANCE + 500);
+
+		// Only every `SweepPeriod` blocks
+		assert_eq!(TemplateModule::on_initialize(9), <() as WeightInfo>::settle_bets(0));
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE + 500);
+
+		System::set_block_number(10);
+		assert!(TemplateModule::on_initialize(10).any_gt(<() as WeightInfo>::settle_bets(0)));
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE);
+		assert_eq!(Balances::free_balance(TREASURY), 500);
+		System::assert_last_event(Event::PotSwept { amount: 500 }.into());
+
+		// Nothing to sweep at the reserve target
+		System::reset_events();
+		TemplateModule::on_initialize(20);
+		assert_eq!(Balances::free_balance(TREASURY), 500);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn pot_below_reserve_target_is_not_swept_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&pot, POT_BALANCE - 1);
+
+		assert_eq!(TemplateModule::sweep_pot(), 0);
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE - 1);
+
+		// Sweeping is disabled by default in the mock
+		Balances::make_free_balance_be(&pot, POT_BALANCE * 2);
+		assert_eq!(TemplateModule::on_initialize(10), <() as WeightInfo>::settle_bets(0));
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE * 2);
+	});
+}
+
+#[test]
+fn bankroll_deposits_mint_shares_priced_from_the_pot_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&ALICE, POT_BALANCE);
+		Balances::make_free_balance_be(&BOB, POT_BALANCE);
+
+		// The house keeps one share per unit it held before the first deposit
+		assert_ok!(TemplateModule::deposit_bankroll(RuntimeOrigin::signed(ALICE), 1_000));
+		assert_eq!(BankrollShares::<Test>::get(pot), POT_BALANCE);
+		assert_eq!(BankrollShares::<Test>::get(ALICE), 1_000);
+		assert_eq!(TotalBankrollShares::<Test>::get(), POT_BALANCE + 1_000);
+		System::assert_last_event(Event::BankrollDeposited { who: ALICE, amount: 1_000, shares: 1_000 }.into());
+
+		// The pot doubles, so later shares cost twice as much and earlier ones are worth twice as much
+		Balances::make_free_balance_be(&pot, (POT_BALANCE + 1_000) * 2);
+		assert_ok!(TemplateModule::deposit_bankroll(RuntimeOrigin::signed(BOB), 1_000));
+		assert_eq!(BankrollShares::<Test>::get(BOB), 500);
+		assert_eq!(TemplateModule::bankroll_value(1_000), 2_000);
+
+		// Deposits worth less than a share are rejected
+		assert_noop!(
+			TemplateModule::deposit_bankroll(RuntimeOrigin::signed(BOB), 1),
+			Error::<Test>::InvalidBankrollAmount
+		);
+	});
+}
+
+#[test]
+fn bankroll_withdrawals_open_after_the_cooldown_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&ALICE, POT_BALANCE);
+		assert_ok!(TemplateModule::deposit_bankroll(RuntimeOrigin::signed(ALICE), 1_000));
+
+		System::set_block_number(BANKROLL_COOLDOWN);
+		assert_noop!(
+			TemplateModule::withdraw_bankroll(RuntimeOrigin::signed(ALICE), 1_000),
+			Error::<Test>::BankrollLocked
+		);
+		assert_noop!(
+			TemplateModule::withdraw_bankroll(RuntimeOrigin::signed(BOB), 1),
+			Error::<Test>::InsufficientShares
+		);
+
+		// Liquidity providers share the losses of the pot
+		Balances::make_free_balance_be(&pot, (POT_BALANCE + 1_000) / 2);
+		System::set_block_number(1 + BANKROLL_COOLDOWN);
+		assert_noop!(
+			TemplateModule::withdraw_bankroll(RuntimeOrigin::signed(ALICE), 1_001),
+			Error::<Test>::InsufficientShares
+		);
+		assert_ok!(TemplateModule::withdraw_bankroll(RuntimeOrigin::signed(ALICE), 400));
+		System::assert_last_event(Event::BankrollWithdrawn { who: ALICE, shares: 400, amount: 200 }.into());
+		assert_ok!(TemplateModule::withdraw_bankroll(RuntimeOrigin::signed(ALICE), 600));
+		assert_eq!(Balances::free_balance(ALICE), POT_BALANCE - 500);
+		assert!(!BankrollShares::<Test>::contains_key(ALICE));
+		assert_eq!(TotalBankrollShares::<Test>::get(), POT_BALANCE);
+	});
+}
+
+#[test]
+fn sweeps_never_take_liquidity_provider_shares_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&ALICE, POT_BALANCE);
+		assert_ok!(TemplateModule::deposit_bankroll(RuntimeOrigin::signed(ALICE), POT_BALANCE / 2));
+
+		// Only the house's stake above the reserve target is swept
+		assert_eq!(TemplateModule::sweep_pot(), 0);
+		Balances::make_free_balance_be(&pot, POT_BALANCE * 3);
+		assert_eq!(TemplateModule::sweep_pot(), POT_BALANCE);
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE * 2);
+		assert_eq!(TemplateModule::bankroll_value(BankrollShares::<Test>::get(pot)), POT_BALANCE);
+		assert_eq!(TemplateModule::bankroll_value(BankrollShares::<Test>::get(ALICE)), POT_BALANCE);
+	});
+}
+
+#[test]
+fn sponsored_fees_are_paid_by_the_house_stake_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&ALICE, POT_BALANCE);
+		assert_ok!(TemplateModule::deposit_bankroll(RuntimeOrigin::signed(ALICE), POT_BALANCE / 2));
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(BOB)));
+
+		// BOB holds no funds, the pot pays for his toss out of the house's shares
+		assert_ok!(pay_fees(BOB, crate::Call::do_toss {}));
+		assert!(Balances::free_balance(pot) < POT_BALANCE + POT_BALANCE / 2);
+		assert!(BankrollShares::<Test>::get(pot) < POT_BALANCE);
+		assert!(TemplateModule::bankroll_value(BankrollShares::<Test>::get(ALICE)) >= POT_BALANCE / 2);
+	});
+}
+
+#[test]
+fn sponsorship_stops_once_the_house_stake_cannot_pay_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&pot, 1_000);
+		Balances::make_free_balance_be(&ALICE, POT_BALANCE);
+		assert_ok!(TemplateModule::deposit_bankroll(RuntimeOrigin::signed(ALICE), POT_BALANCE / 2));
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(BOB)));
+
+		// The fee is worth more than the house's 1_000 shares, liquidity providers never pay it
+		assert_eq!(
+			charge_fees(BOB, crate::Call::do_toss {}, 0),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(Balances::free_balance(pot), 1_000 + POT_BALANCE / 2);
+		assert_eq!(TemplateModule::sponsored_calls_left(&BOB), SPONSORED_CALLS_PER_ERA);
+	});
+}
+
+#[test]
+fn sponsored_fees_leave_the_payouts_of_open_bets_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		// The whole pot is set aside for open bets, none of it pays fees
+		TotalExposure::<Test>::put(POT_BALANCE);
+		assert_eq!(TemplateModule::house_stake(), 0);
+		assert_eq!(
+			charge_fees(ALICE, crate::Call::do_toss {}, 0),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE);
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), SPONSORED_CALLS_PER_ERA);
+
+		// Fees are sponsored again from what the pot holds beyond the payouts
+		TotalExposure::<Test>::put(POT_BALANCE / 2);
+		assert_ok!(charge_fees(ALICE, crate::Call::do_toss {}, 0));
+		assert!(Balances::free_balance(pot) >= POT_BALANCE / 2);
+		assert_eq!(TemplateModule::sponsored_calls_left(&ALICE), SPONSORED_CALLS_PER_ERA - 1);
+	});
+}
+
+#[test]
+fn max_bet_is_the_kelly_fraction_of_the_pot_test() {
+	new_test_ext().execute_with(|| {
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&pot, 10_000);
+
+		// The house edge of the mock is 2%
+		assert_eq!(TemplateModule::max_bet(), 200);
+		assert_ok!(TemplateModule::ensure_within_risk_limit(200));
+		assert_noop!(TemplateModule::ensure_within_risk_limit(201), Error::<Test>::BetExceedsRiskLimit);
+
+		// The limit follows the pot
+		Balances::make_free_balance_be(&pot, 1_000);
+		assert_eq!(TemplateModule::max_bet(), 20);
+		assert_noop!(TemplateModule::ensure_within_risk_limit(200), Error::<Test>::BetExceedsRiskLimit);
+	});
+}
+
+#[test]
+fn max_bet_sets_outstanding_exposure_aside_test() {
+	assert_eq!(kelly_max_bet(10_000u64, Permill::from_percent(2), 5_000), 100);
+	assert_eq!(kelly_max_bet(10_000u64, Permill::from_percent(2), 20_000), 0);
+	assert_eq!(kelly_max_bet(10_000u64, Permill::zero(), 0), 0);
+}
+
+// Moves to the block in which bets placed in the current block are settled, and settles them
+fn run_to_bet_settlement() {
+	let settle_at = System::block_number() + BET_SETTLEMENT_DELAY;
+	System::set_block_number(settle_at);
+	TemplateModule::on_initialize(settle_at);
+}
+
+#[test]
+fn bets_are_settled_by_a_later_toss_of_the_pallet_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&ALICE, 1_000);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		// A won bet pays twice the stake less the 2% house edge
+		assert_ok!(TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Head, 100));
+		System::assert_last_event(Event::BetPlaced { who: ALICE, side: CoinSide::Head, stake: 100, payout: 196, settle_at: 3 }.into());
+		assert_eq!(Balances::free_balance(ALICE), 900);
+		assert_eq!(TotalExposure::<Test>::get(), 196);
+		assert_eq!(BetsDue::<Test>::get(3).into_inner(), vec![ALICE]);
+
+		// The bettor's own tosses settle nothing
+		force_next_toss(CoinSide::Head);
+		assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)));
+		assert!(OpenBets::<Test>::contains_key(ALICE));
+
+		force_next_toss(CoinSide::Head);
+		run_to_bet_settlement();
+		System::assert_has_event(Event::CoinTossed { who: ALICE, side: CoinSide::Head, nonce: 1 }.into());
+		System::assert_last_event(Event::BetSettled { who: ALICE, side: CoinSide::Head, payout: 196 }.into());
+		assert_eq!(Balances::free_balance(ALICE), 1_096);
+		assert_eq!(TotalExposure::<Test>::get(), 0);
+		assert!(!BetsDue::<Test>::contains_key(3));
+
+		// A lost stake stays in the pot
+		assert_ok!(TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Head, 100));
+		force_next_toss(CoinSide::Tail);
+		run_to_bet_settlement();
+		System::assert_last_event(Event::BetSettled { who: ALICE, side: CoinSide::Tail, payout: 0 }.into());
+		assert_eq!(Balances::free_balance(ALICE), 996);
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE + 4);
+		assert!(!OpenBets::<Test>::contains_key(ALICE));
+		assert_randomness_consumed();
+	});
+}
+
+#[test]
+fn removed_coins_forfeit_their_bets_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&ALICE, 1_000);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Head, 100));
+
+		TemplateModule::on_killed_account(&ALICE);
+		assert!(!OpenBets::<Test>::contains_key(ALICE));
+		assert_eq!(TotalExposure::<Test>::get(), 0);
+
+		// The settlement block skips the forfeited bet without tossing
+		System::reset_events();
+		run_to_bet_settlement();
+		assert!(System::events().is_empty());
+		assert_eq!(Balances::free_balance(pot), POT_BALANCE + 100);
+	});
+}
+
+#[test]
+fn bets_are_checked_before_they_are_placed_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&pot, 10_000);
+		Balances::make_free_balance_be(&ALICE, 1_000);
+		assert_noop!(
+			TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Head, 100),
+			Error::<Test>::CoinDoesNotExist
+		);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		assert_noop!(
+			TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Head, 0),
+			Error::<Test>::InvalidStake
+		);
+		assert_noop!(
+			TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Head, 201),
+			Error::<Test>::BetExceedsRiskLimit
+		);
+		assert_ok!(TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Tail, 200));
+		assert_noop!(
+			TemplateModule::place_bet(RuntimeOrigin::signed(ALICE), CoinSide::Tail, 1),
+			Error::<Test>::BetAlreadyOpen
+		);
+
+		// The payout of the open bet is set aside before applying the Kelly fraction
+		assert_eq!(TotalExposure::<Test>::get(), 392);
+		assert_eq!(TemplateModule::max_bet(), kelly_max_bet(10_200, Permill::from_percent(2), 392));
+
+		// Only `MaxBetsPerBlock` bets are settled in one block
+		for bettor in [BOB, CHARLIE] {
+			Balances::make_free_balance_be(&bettor, 1_000);
+			assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(bettor)));
+		}
+		assert_ok!(TemplateModule::place_bet(RuntimeOrigin::signed(BOB), CoinSide::Head, 10));
+		assert_noop!(
+			TemplateModule::place_bet(RuntimeOrigin::signed(CHARLIE), CoinSide::Head, 10),
+			Error::<Test>::TooManyBetsDue
+		);
+		System::set_block_number(2);
+		assert_ok!(TemplateModule::place_bet(RuntimeOrigin::signed(CHARLIE), CoinSide::Head, 10));
+	});
+}
+
+#[test]
+fn bankroll_withdrawals_leave_open_bets_covered_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let pot = TemplateModule::account_id();
+		Balances::make_free_balance_be(&pot, 100);
+		Balances::make_free_balance_be(&ALICE, 20_000);
+		Balances::make_free_balance_be(&BOB, 1_000);
+		assert_ok!(TemplateModule::deposit_bankroll(RuntimeOrigin::signed(ALICE), 10_000));
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(BOB)));
+
+		System::set_block_number(1 + BANKROLL_COOLDOWN);
+		assert_ok!(TemplateModule::place_bet(RuntimeOrigin::signed(BOB), CoinSide::Head, 200));
+		assert_eq!(Balances::free_balance(pot), 10_300);
+		assert_eq!(TotalExposure::<Test>::get(), 392);
+
+		// Shares are priced without the payout of the open bet, so withdrawing all of them leaves
+		// it in the pot
+		assert_eq!(TemplateModule::bankroll_value(10_000), 10_000 * 9_908 / 10_100);
+		assert_ok!(TemplateModule::withdraw_bankroll(RuntimeOrigin::signed(ALICE), 10_000));
+		System::assert_last_event(Event::BankrollWithdrawn { who: ALICE, shares: 10_000, amount: 9_809 }.into());
+		assert_eq!(Balances::free_balance(pot), 491);
+		assert_eq!(TemplateModule::house_stake(), 99);
+
+		// New shares are priced the same way
+		assert_eq!(TemplateModule::shares_for(99), 100);
+
+		// The won bet is still paid out in full
+		force_next_toss(CoinSide::Head);
+		run_to_bet_settlement();
+		assert_eq!(Balances::free_balance(BOB), 1_192);
+		assert_eq!(Balances::free_balance(pot), 99);
+		assert_eq!(TotalExposure::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn cooling_off_pauses_play_until_it_ends_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_noop!(TemplateModule::cool_off(RuntimeOrigin::signed(ALICE), 0), Error::<Test>::InvalidPlayerLimit);
+
+		assert_ok!(TemplateModule::cool_off(RuntimeOrigin::signed(ALICE), 10));
+		System::assert_last_event(Event::CoolingOffStarted { who: ALICE, until: 11 }.into());
+		assert_noop!(TemplateModule::do_flip(RuntimeOrigin::signed(ALICE)), Error::<Test>::CoolingOff);
+		assert_noop!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)), Error::<Test>::CoolingOff);
+		assert_noop!(TemplateModule::schedule_tosses(RuntimeOrigin::signed(ALICE), 1, 1), Error::<Test>::CoolingOff);
+
+		// A shorter period does not end it early
+		assert_ok!(TemplateModule::cool_off(RuntimeOrigin::signed(ALICE), 1));
+		System::assert_last_event(Event::CoolingOffStarted { who: ALICE, until: 11 }.into());
+
+		System::set_block_number(11);
+		assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)));
+	});
+}
+
+#[test]
+fn self_exclusion_lasts_until_lifted_after_its_term_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::approve_operator(RuntimeOrigin::signed(ALICE), BOB, None));
+		assert_noop!(TemplateModule::lift_self_exclusion(RuntimeOrigin::signed(ALICE)), Error::<Test>::NotSelfExcluded);
+
+		assert_ok!(TemplateModule::self_exclude(RuntimeOrigin::signed(ALICE), 100));
+		System::assert_last_event(Event::SelfExcluded { who: ALICE, until: 101 }.into());
+		assert_noop!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)), Error::<Test>::SelfExcluded);
+
+		// Operators cannot play for an excluded owner
+		assert_noop!(TemplateModule::toss_for(RuntimeOrigin::signed(BOB), ALICE), Error::<Test>::SelfExcluded);
+		assert_noop!(TemplateModule::flip_for(RuntimeOrigin::signed(BOB), ALICE), Error::<Test>::SelfExcluded);
+
+		System::set_block_number(100);
+		assert_noop!(
+			TemplateModule::lift_self_exclusion(RuntimeOrigin::signed(ALICE)),
+			Error::<Test>::SelfExclusionNotOver
+		);
+
+		// The exclusion does not end by itself
+		System::set_block_number(1_000);
+		assert_noop!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)), Error::<Test>::SelfExcluded);
+
+		assert_ok!(TemplateModule::lift_self_exclusion(RuntimeOrigin::signed(ALICE)));
+		System::assert_last_event(Event::SelfExclusionLifted { who: ALICE }.into());
+		assert_ok!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)));
+	});
+}
+
+#[test]
+fn self_exclusion_survives_account_removal_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::self_exclude(RuntimeOrigin::signed(ALICE), 100));
+
+		TemplateModule::on_killed_account(&ALICE);
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_noop!(TemplateModule::do_toss(RuntimeOrigin::signed(ALICE)), Error::<Test>::SelfExcluded);
+	});
+}
+
+#[test]
+fn loss_limits_apply_to_rolling_windows_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::ensure_within_loss_limits(&ALICE, u64::MAX));
+
+		assert_ok!(TemplateModule::set_loss_limits(RuntimeOrigin::signed(ALICE), Some(100), Some(250)));
+		System::assert_last_event(Event::LossLimitsSet { who: ALICE, daily: Some(100), weekly: Some(250) }.into());
+
+		TemplateModule::note_loss(&ALICE, 60);
+		assert_ok!(TemplateModule::ensure_within_loss_limits(&ALICE, 40));
+		assert_noop!(TemplateModule::ensure_within_loss_limits(&ALICE, 41), Error::<Test>::LossLimitExceeded);
+
+		// The daily window rolls past the first loss, the weekly one does not
+		System::set_block_number(1 + LOSS_LIMIT_DAY);
+		assert_eq!(TemplateModule::losses_within(&ALICE, LOSS_LIMIT_DAY), 0);
+		TemplateModule::note_loss(&ALICE, 100);
+		System::set_block_number(1 + 2 * LOSS_LIMIT_DAY);
+		TemplateModule::note_loss(&ALICE, 80);
+		assert_eq!(TemplateModule::losses_within(&ALICE, 7 * LOSS_LIMIT_DAY), 240);
+		assert_noop!(TemplateModule::ensure_within_loss_limits(&ALICE, 11), Error::<Test>::LossLimitExceeded);
+
+		// A week after the first loss, it no longer counts
+		System::set_block_number(1 + 7 * LOSS_LIMIT_DAY);
+		assert_ok!(TemplateModule::ensure_within_loss_limits(&ALICE, 70));
+	});
+}
+
+#[test]
+fn lost_bets_count_towards_loss_limits_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&ALICE, 1_000);
+		let alice = RuntimeOrigin::signed(ALICE);
+		assert_ok!(TemplateModule::create_coin(alice.clone()));
+		assert_ok!(TemplateModule::set_loss_limits(alice.clone(), Some(250), None));
+
+		// Every toss lands on tails, so every bet on heads is lost until the daily limit trips
+		let bet = RuntimeCall::TemplateModule(crate::Call::place_bet { side: CoinSide::Head, stake: 100 });
+		let mut lost = 0;
+		while bet.clone().dispatch(alice.clone()).is_ok() {
+			force_next_toss(CoinSide::Tail);
+			run_to_bet_settlement();
+			lost += 1;
+		}
+		assert_eq!(lost, 2);
+		assert_eq!(TemplateModule::losses_within(&ALICE, LOSS_LIMIT_DAY), 200);
+		assert_noop!(bet.clone().dispatch(alice.clone()), Error::<Test>::LossLimitExceeded);
+
+		// Smaller bets still fit, and the limit is back a day later
+		assert_ok!(TemplateModule::place_bet(alice.clone(), CoinSide::Head, 50));
+		force_next_toss(CoinSide::Head);
+		run_to_bet_settlement();
+		assert_eq!(TemplateModule::losses_within(&ALICE, LOSS_LIMIT_DAY), 200);
+		System::set_block_number(System::block_number() + LOSS_LIMIT_DAY);
+		assert_ok!(bet.dispatch(alice));
+		assert_randomness_consumed();
+	});
+}
+
+#[test]
+fn loss_limits_cannot_be_loosened_while_restricted_test() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::set_loss_limits(RuntimeOrigin::signed(ALICE), Some(100), None));
+		assert_ok!(TemplateModule::cool_off(RuntimeOrigin::signed(ALICE), 10));
+
+		assert_noop!(
+			TemplateModule::set_loss_limits(RuntimeOrigin::signed(ALICE), Some(200), None),
+			Error::<Test>::CoolingOff
+		);
+		assert_noop!(
+			TemplateModule::set_loss_limits(RuntimeOrigin::signed(ALICE), None, None),
+			Error::<Test>::CoolingOff
+		);
+		assert_ok!(TemplateModule::set_loss_limits(RuntimeOrigin::signed(ALICE), Some(50), Some(300)));
+
+		System::set_block_number(11);
+		assert_ok!(TemplateModule::set_loss_limits(RuntimeOrigin::signed(ALICE), None, None));
+		assert_eq!(PlayerLimitsOf::<Test>::get(ALICE).unwrap().daily_loss_limit, None);
 	});
 }
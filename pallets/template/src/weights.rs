@@ -1,29 +1,15 @@
-
-//! Autogenerated weights for pallet_template
+//! Weights for pallet_template
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-04-06, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `Alexs-MacBook-Pro-2.local`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ../../target/release/node-template
-// benchmark
-// pallet
-// --chain
-// dev
-// --pallet
-// pallet_template
-// --extrinsic
-// *
-// --steps=50
-// --repeat=20
-// --wasm-execution=compiled
-// --output
-// pallets/template/src/weights.rs
-// --template
-// ../../.maintain/frame-weight-template.hbs
+//! THESE WEIGHTS ARE HAND-WRITTEN ESTIMATES, NOT BENCHMARK RESULTS. The storage accesses of each
+//! entry follow the code of its call, while the execution times and proof sizes are estimates.
+//! Replace this file with the output of the benchmarks on reference hardware before relying on
+//! these weights in production:
+//!
+//! ```sh
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_template \
+//!     --extrinsic '*' --steps 50 --repeat 20 --wasm-execution compiled \
+//!     --output pallets/template/src/weights.rs
+//! ```
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -34,57 +20,498 @@ use core::marker::PhantomData;
 
 /// Weight functions needed for pallet_template.
 pub trait WeightInfo {
-	fn do_something() -> Weight;
-	fn cause_error() -> Weight;
+	fn schedule_tosses() -> Weight;
+	fn cancel_toss_schedule() -> Weight;
+	fn approve_operator() -> Weight;
+	fn revoke_operator() -> Weight;
+	fn flip_for() -> Weight;
+	fn toss_for() -> Weight;
+	fn set_sponsorship_quota() -> Weight;
+	fn sweep_pot() -> Weight;
+	fn deposit_bankroll() -> Weight;
+	fn withdraw_bankroll() -> Weight;
+	fn do_toss() -> Weight;
+	fn place_bet() -> Weight;
+	fn cool_off() -> Weight;
+	fn self_exclude() -> Weight;
+	fn lift_self_exclusion() -> Weight;
+	fn set_loss_limits() -> Weight;
+	fn settle_bets(b: u32, ) -> Weight;
+	fn create_coin() -> Weight;
+	fn do_flip() -> Weight;
 }
 
-/// Weights for pallet_template using the Substrate node and recommended hardware.
+/// Estimated weights for pallet_template, see the module documentation.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	/// Storage: TemplateModule Something (r:0 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	/// Storage: TemplateModule CoinStorage (r:1 w:0)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossSchedules (r:1 w:1)
+	/// Proof: TemplateModule TossSchedules (max_values: None, max_size: Some(150), added: 2625, mode: MaxEncodedLen)
+	/// Storage: TemplateModule NextTossScheduleId (r:1 w:1)
+	/// Proof: TemplateModule NextTossScheduleId (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Proof: Scheduler Lookup (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Proof: Scheduler Agenda (max_values: None, max_size: Some(38963), added: 41438, mode: MaxEncodedLen)
+	fn schedule_tosses() -> Weight {
+		Weight::from_parts(61_000_000, 42428)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: TemplateModule TossSchedules (r:1 w:1)
+	/// Proof: TemplateModule TossSchedules (max_values: None, max_size: Some(150), added: 2625, mode: MaxEncodedLen)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Proof: Scheduler Lookup (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Proof: Scheduler Agenda (max_values: None, max_size: Some(38963), added: 41438, mode: MaxEncodedLen)
+	fn cancel_toss_schedule() -> Weight {
+		Weight::from_parts(33_000_000, 42428)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: TemplateModule CoinStorage (r:1 w:0)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinOperators (r:1 w:1)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	fn approve_operator() -> Weight {
+		Weight::from_parts(25_000_000, 4106)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
-	/// Storage: TemplateModule Something (r:1 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	/// Storage: TemplateModule CoinOperators (r:1 w:1)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	fn revoke_operator() -> Weight {
+		Weight::from_parts(18_000_000, 4106)
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: TemplateModule CoinOperators (r:1 w:0)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn flip_for() -> Weight {
+		Weight::from_parts(28_000_000, 4106)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: TemplateModule CoinOperators (r:1 w:0)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossNonce (r:1 w:1)
+	/// Proof: TemplateModule TossNonce (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn toss_for() -> Weight {
+		Weight::from_parts(38_000_000, 4106)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: TemplateModule SponsorshipQuota (r:0 w:1)
+	/// Proof: TemplateModule SponsorshipQuota (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn set_sponsorship_quota() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule BankrollShares (r:1 w:1)
+	/// Proof: TemplateModule BankrollShares (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalBankrollShares (r:1 w:1)
+	/// Proof: TemplateModule TotalBankrollShares (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:0)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn sweep_pot() -> Weight {
+		Weight::from_parts(48_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: TemplateModule TotalBankrollShares (r:1 w:1)
+	/// Proof: TemplateModule TotalBankrollShares (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule BankrollShares (r:1 w:2)
+	/// Proof: TemplateModule BankrollShares (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule LastBankrollDeposit (r:0 w:1)
+	/// Proof: TemplateModule LastBankrollDeposit (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn deposit_bankroll() -> Weight {
+		Weight::from_parts(54_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: TemplateModule TotalBankrollShares (r:1 w:1)
+	/// Proof: TemplateModule TotalBankrollShares (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule BankrollShares (r:1 w:1)
+	/// Proof: TemplateModule BankrollShares (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule LastBankrollDeposit (r:1 w:1)
+	/// Proof: TemplateModule LastBankrollDeposit (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:0)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	fn withdraw_bankroll() -> Weight {
+		Weight::from_parts(57_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossNonce (r:1 w:1)
+	/// Proof: TemplateModule TossNonce (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn do_toss() -> Weight {
+		Weight::from_parts(35_000_000, 3556)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: TemplateModule CoinStorage (r:1 w:0)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule OpenBets (r:1 w:1)
+	/// Proof: TemplateModule OpenBets (max_values: None, max_size: Some(81), added: 2556, mode: MaxEncodedLen)
+	/// Storage: TemplateModule RecentLosses (r:1 w:0)
+	/// Proof: TemplateModule RecentLosses (max_values: None, max_size: Some(3430), added: 5905, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule BetsDue (r:1 w:1)
+	/// Proof: TemplateModule BetsDue (max_values: None, max_size: Some(2069), added: 4544, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:1)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	fn place_bet() -> Weight {
+		Weight::from_parts(65_000_000, 6895)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn cool_off() -> Weight {
+		Weight::from_parts(15_000_000, 3556)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn self_exclude() -> Weight {
+		Weight::from_parts(15_000_000, 3556)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn lift_self_exclusion() -> Weight {
+		Weight::from_parts(17_000_000, 3556)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn set_loss_limits() -> Weight {
+		Weight::from_parts(17_000_000, 3556)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule BetsDue (r:1 w:1)
+	/// Proof: TemplateModule BetsDue (max_values: None, max_size: Some(2069), added: 4544, mode: MaxEncodedLen)
+	/// Storage: TemplateModule OpenBets (r:1 w:1)
+	/// Proof: TemplateModule OpenBets (max_values: None, max_size: Some(81), added: 2556, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossNonce (r:1 w:1)
+	/// Proof: TemplateModule TossNonce (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:1)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// The range of component `b` is `[0, 64]`.
+	fn settle_bets(b: u32, ) -> Weight {
+		Weight::from_parts(7_000_000, 5534)
+			.saturating_add(Weight::from_parts(47_000_000, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(b.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(b.into()))
+	}
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn create_coin() -> Weight {
+		Weight::from_parts(18_000_000, 3514)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn do_flip() -> Weight {
+		Weight::from_parts(29_000_000, 3556)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	/// Storage: TemplateModule Something (r:0 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	/// Storage: TemplateModule CoinStorage (r:1 w:0)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossSchedules (r:1 w:1)
+	/// Proof: TemplateModule TossSchedules (max_values: None, max_size: Some(150), added: 2625, mode: MaxEncodedLen)
+	/// Storage: TemplateModule NextTossScheduleId (r:1 w:1)
+	/// Proof: TemplateModule NextTossScheduleId (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Proof: Scheduler Lookup (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Proof: Scheduler Agenda (max_values: None, max_size: Some(38963), added: 41438, mode: MaxEncodedLen)
+	fn schedule_tosses() -> Weight {
+		Weight::from_parts(61_000_000, 42428)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: TemplateModule TossSchedules (r:1 w:1)
+	/// Proof: TemplateModule TossSchedules (max_values: None, max_size: Some(150), added: 2625, mode: MaxEncodedLen)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Proof: Scheduler Lookup (max_values: None, max_size: Some(48), added: 2523, mode: MaxEncodedLen)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Proof: Scheduler Agenda (max_values: None, max_size: Some(38963), added: 41438, mode: MaxEncodedLen)
+	fn cancel_toss_schedule() -> Weight {
+		Weight::from_parts(33_000_000, 42428)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: TemplateModule CoinStorage (r:1 w:0)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinOperators (r:1 w:1)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	fn approve_operator() -> Weight {
+		Weight::from_parts(25_000_000, 4106)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule CoinOperators (r:1 w:1)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	fn revoke_operator() -> Weight {
+		Weight::from_parts(18_000_000, 4106)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule CoinOperators (r:1 w:0)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn flip_for() -> Weight {
+		Weight::from_parts(28_000_000, 4106)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: TemplateModule CoinOperators (r:1 w:0)
+	/// Proof: TemplateModule CoinOperators (max_values: None, max_size: Some(641), added: 3116, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossNonce (r:1 w:1)
+	/// Proof: TemplateModule TossNonce (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn toss_for() -> Weight {
+		Weight::from_parts(38_000_000, 4106)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: TemplateModule SponsorshipQuota (r:0 w:1)
+	/// Proof: TemplateModule SponsorshipQuota (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn set_sponsorship_quota() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule BankrollShares (r:1 w:1)
+	/// Proof: TemplateModule BankrollShares (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalBankrollShares (r:1 w:1)
+	/// Proof: TemplateModule TotalBankrollShares (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:0)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn sweep_pot() -> Weight {
+		Weight::from_parts(48_000_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: TemplateModule TotalBankrollShares (r:1 w:1)
+	/// Proof: TemplateModule TotalBankrollShares (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule BankrollShares (r:1 w:2)
+	/// Proof: TemplateModule BankrollShares (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule LastBankrollDeposit (r:0 w:1)
+	/// Proof: TemplateModule LastBankrollDeposit (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn deposit_bankroll() -> Weight {
+		Weight::from_parts(54_000_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: TemplateModule TotalBankrollShares (r:1 w:1)
+	/// Proof: TemplateModule TotalBankrollShares (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule BankrollShares (r:1 w:1)
+	/// Proof: TemplateModule BankrollShares (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule LastBankrollDeposit (r:1 w:1)
+	/// Proof: TemplateModule LastBankrollDeposit (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:0)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	fn withdraw_bankroll() -> Weight {
+		Weight::from_parts(57_000_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossNonce (r:1 w:1)
+	/// Proof: TemplateModule TossNonce (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn do_toss() -> Weight {
+		Weight::from_parts(35_000_000, 3556)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: TemplateModule CoinStorage (r:1 w:0)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule OpenBets (r:1 w:1)
+	/// Proof: TemplateModule OpenBets (max_values: None, max_size: Some(81), added: 2556, mode: MaxEncodedLen)
+	/// Storage: TemplateModule RecentLosses (r:1 w:0)
+	/// Proof: TemplateModule RecentLosses (max_values: None, max_size: Some(3430), added: 5905, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule BetsDue (r:1 w:1)
+	/// Proof: TemplateModule BetsDue (max_values: None, max_size: Some(2069), added: 4544, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:1)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	fn place_bet() -> Weight {
+		Weight::from_parts(65_000_000, 6895)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn cool_off() -> Weight {
+		Weight::from_parts(15_000_000, 3556)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn self_exclude() -> Weight {
+		Weight::from_parts(15_000_000, 3556)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
-	/// Storage: TemplateModule Something (r:1 w:1)
-	/// Proof: TemplateModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn lift_self_exclusion() -> Weight {
+		Weight::from_parts(17_000_000, 3556)
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:1)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	fn set_loss_limits() -> Weight {
+		Weight::from_parts(17_000_000, 3556)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TemplateModule BetsDue (r:1 w:1)
+	/// Proof: TemplateModule BetsDue (max_values: None, max_size: Some(2069), added: 4544, mode: MaxEncodedLen)
+	/// Storage: TemplateModule OpenBets (r:1 w:1)
+	/// Proof: TemplateModule OpenBets (max_values: None, max_size: Some(81), added: 2556, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossNonce (r:1 w:1)
+	/// Proof: TemplateModule TossNonce (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TotalExposure (r:1 w:1)
+	/// Proof: TemplateModule TotalExposure (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// The range of component `b` is `[0, 64]`.
+	fn settle_bets(b: u32, ) -> Weight {
+		Weight::from_parts(7_000_000, 5534)
+			.saturating_add(Weight::from_parts(47_000_000, 0).saturating_mul(b.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((6_u64).saturating_mul(b.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(b.into()))
+	}
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn create_coin() -> Weight {
+		Weight::from_parts(18_000_000, 3514)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: TemplateModule PlayerLimitsOf (r:1 w:0)
+	/// Proof: TemplateModule PlayerLimitsOf (max_values: None, max_size: Some(91), added: 2566, mode: MaxEncodedLen)
+	/// Storage: TemplateModule TossActivityOf (r:1 w:1)
+	/// Proof: TemplateModule TossActivityOf (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinStorage (r:1 w:1)
+	/// Proof: TemplateModule CoinStorage (max_values: None, max_size: Some(49), added: 2524, mode: MaxEncodedLen)
+	/// Storage: TemplateModule CoinLastActive (r:0 w:1)
+	/// Proof: TemplateModule CoinLastActive (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	fn do_flip() -> Weight {
+		Weight::from_parts(29_000_000, 3556)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
 }
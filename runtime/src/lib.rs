@@ -6,6 +6,7 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::{Decode, Encode, MaxEncodedLen};
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -16,7 +17,7 @@ use sp_runtime::{
 		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, MultiSignature, RuntimeDebug,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -27,8 +28,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
-		StorageInfo,
+		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, EqualPrivilegeOnly, InstanceFilter,
+		KeyOwnerProofSystem, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -39,7 +40,9 @@ pub use frame_support::{
 	StorageValue,
 };
 pub use frame_system::Call as SystemCall;
+use frame_system::{EnsureNever, EnsureRoot};
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_template::Call as TemplateModuleCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -49,10 +52,13 @@ pub use sp_runtime::{Perbill, Permill};
 /// Import the template pallet.
 pub use pallet_template;
 
-use frame_support::PalletId;
+use frame_support::{traits::EitherOfDiverse, PalletId};
 
 pub use pallet_insecure_randomness_collective_flip;
 
+#[cfg(test)]
+mod tests;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -106,12 +112,12 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+	// This value started at 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -193,8 +199,8 @@ impl frame_system::Config for Runtime {
 	type PalletInfo = PalletInfo;
 	/// What to do if a new account is created.
 	type OnNewAccount = ();
-	/// What to do if an account is fully reaped from the system.
-	type OnKilledAccount = ();
+	/// What to do if an account is fully reaped from the system: remove its coin-flipper state.
+	type OnKilledAccount = TemplateModule;
 	/// The data to be stored in an account.
 	type AccountData = pallet_balances::AccountData<Balance>;
 	/// Weight information for the extrinsics of this pallet.
@@ -259,33 +265,272 @@ parameter_types! {
 
 parameter_types! {
 	pub const CoinFlipperPalletId: PalletId = PalletId(*b"coinflip");
+	/// Scheduled tosses pay no transaction fees, so they are paid up front at about twice the
+	/// fee of a toss transaction.
+	pub const TossScheduleFee: Balance = 500_000_000;
 }
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	/// Fees of coin flips and tosses are paid by the coin-flipper pot, within a quota.
+	type OnChargeTransaction =
+		pallet_template::SponsoredTransactionCharge<Runtime, CurrencyAdapter<Balances, ()>>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type WeightToFee = IdentityFee<Balance>;
 	type LengthToFee = IdentityFee<Balance>;
 	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
 }
 
-impl pallet_sudo::Config for Runtime {
-	type RuntimeEvent = RuntimeEvent;
-	type RuntimeCall = RuntimeCall;
-	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
-}
-
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
 	type PalletId = CoinFlipperPalletId;
 	type MyRandomness = RandomnessCollectiveFlip;
+	type SponsoredCallsPerEra = ConstU32<10>;
+	type SponsorshipEra = ConstU32<DAYS>;
+	type MinBlocksBetweenTosses = ConstU32<1>;
+	type MaxTossesPerWindow = ConstU32<60>;
+	type TossWindow = ConstU32<HOURS>;
+	type InactivityPeriod = ConstU32<{ 30 * DAYS }>;
+	type MaxOperators = ConstU32<16>;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type Preimages = Preimage;
+	type MaxTossSchedules = ConstU32<4>;
+	type MaxScheduledTosses = ConstU32<100>;
+	type TossScheduleFee = TossScheduleFee;
+	/// Coin-flipper parameters are governed by the council.
+	type AdminOrigin = EnsureCouncilMajority;
+	type Currency = Balances;
+	/// Game revenue funds treasury proposals.
+	type TreasuryAccount = TreasuryAccount;
+	type PotReserveTarget = PotReserveTarget;
+	type SweepPeriod = ConstU32<DAYS>;
+	type BankrollCooldown = ConstU32<{ 7 * DAYS }>;
+	type HouseEdge = HouseEdge;
+	type LossLimitDay = ConstU32<DAYS>;
+	/// The settlement block's randomness mixes in the hashes of the blocks built after the bet.
+	type BetSettlementDelay = ConstU32<3>;
+	type MaxBetsPerBlock = ConstU32<64>;
 }
 
 impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
 
+/// Deposit for storing `items` storage items taking up `bytes` bytes.
+pub const fn deposit(items: u32, bytes: u32) -> Balance {
+	(items as Balance * 20 + bytes as Balance) * EXISTENTIAL_DEPOSIT
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = deposit(1, 8);
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+	pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Encode,
+	Decode,
+	RuntimeDebug,
+	MaxEncodedLen,
+	scale_info::TypeInfo,
+	Default,
+)]
+pub enum ProxyType {
+	/// Any call.
+	#[default]
+	Any,
+	/// Any call that cannot move funds.
+	NonTransfer,
+	/// Only coin-flipper calls, e.g. for a hot session key that can toss coins but never
+	/// move funds.
+	CoinGame,
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, c: &RuntimeCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::NonTransfer => !matches!(
+				c,
+				RuntimeCall::Balances(..) |
+					RuntimeCall::TemplateModule(
+						pallet_template::Call::deposit_bankroll { .. } |
+							pallet_template::Call::withdraw_bankroll { .. } |
+							pallet_template::Call::place_bet { .. } |
+							pallet_template::Call::schedule_tosses { .. }
+					)
+			),
+			ProxyType::CoinGame => match c {
+				// Session keys play, but never move funds in or out of the pot, pay schedule fees
+				// or hand the coin to other accounts
+				RuntimeCall::TemplateModule(
+					pallet_template::Call::deposit_bankroll { .. } |
+					pallet_template::Call::withdraw_bankroll { .. } |
+					pallet_template::Call::place_bet { .. } |
+					pallet_template::Call::schedule_tosses { .. } |
+					pallet_template::Call::approve_operator { .. },
+				) => false,
+				// Nor do they change the limits the account set on its own play
+				RuntimeCall::TemplateModule(
+					pallet_template::Call::cool_off { .. } |
+					pallet_template::Call::self_exclude { .. } |
+					pallet_template::Call::lift_self_exclusion { .. } |
+					pallet_template::Call::set_loss_limits { .. },
+				) => false,
+				RuntimeCall::TemplateModule(..) => true,
+				_ => false,
+			},
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, ProxyType::CoinGame) => true,
+			_ => false,
+		}
+	}
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 5 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+	pub MaxCollectivesProposalWeight: Weight = Perbill::from_percent(50) * BlockWeights::get().max_block;
+}
+
+/// The council, whose members are managed by `CouncilMembership`.
+pub type CouncilCollective = pallet_collective::Instance1;
+
+/// More than half of the council.
+pub type EnsureCouncilMajority =
+	pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>;
+
+/// Root, as dispatched by the scheduler, or more than half of the council. There is no sudo key,
+/// so root-only calls are scheduled or noted by council motions.
+pub type EnsureRootOrCouncilMajority =
+	EitherOfDiverse<EnsureRoot<AccountId>, EnsureCouncilMajority>;
+
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	/// Members are only set through `CouncilMembership`, never directly, not even by root.
+	type SetMembersOrigin = EnsureNever<()>;
+	type MaxProposalWeight = MaxCollectivesProposalWeight;
+}
+
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AddOrigin = EnsureCouncilMajority;
+	type RemoveOrigin = EnsureCouncilMajority;
+	type SwapOrigin = EnsureCouncilMajority;
+	type ResetOrigin = EnsureCouncilMajority;
+	type PrimeOrigin = EnsureCouncilMajority;
+	type MembershipInitialized = Council;
+	type MembershipChanged = Council;
+	type MaxMembers = CouncilMaxMembers;
+	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = deposit(1, 0);
+	pub const SpendPeriod: BlockNumber = DAYS;
+	pub const Burn: Permill = Permill::from_percent(0);
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const MaxApprovals: u32 = 100;
+	pub TreasuryAccount: AccountId = Treasury::account_id();
+	/// The pot keeps its genesis endowment, only the balance above it is swept.
+	pub const PotReserveTarget: Balance = 1 << 60;
+	/// Single bets may stake at most this fraction of the pot.
+	pub const HouseEdge: Permill = Permill::from_percent(1);
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	type ApproveOrigin = EnsureCouncilMajority;
+	type RejectOrigin = EnsureCouncilMajority;
+	type RuntimeEvent = RuntimeEvent;
+	type OnSlash = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type ProposalBondMaximum = ();
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type BurnDestination = ();
+	type SpendFunds = ();
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = MaxApprovals;
+	type SpendOrigin = frame_support::traits::NeverEnsureOrigin<Balance>;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+	pub const PreimageByteDeposit: Balance = deposit(0, 1);
+}
+
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRootOrCouncilMajority;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRootOrCouncilMajority;
+	type MaxScheduledPerBlock = ConstU32<512>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = ConstU32<32>;
+	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type MaxPending = ConstU32<32>;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub struct Runtime {
@@ -296,10 +541,15 @@ construct_runtime!(
 		Grandpa: pallet_grandpa,
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
-		Sudo: pallet_sudo,
 		//RandomnessCollectiveFlip: pallet_insecure_randomness_collective_flip,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
+		Proxy: pallet_proxy,
+		Preimage: pallet_preimage,
+		Scheduler: pallet_scheduler,
+		Council: pallet_collective::<Instance1>,
+		CouncilMembership: pallet_membership::<Instance1>,
+		Treasury: pallet_treasury,
 	}
 );
 
@@ -319,6 +569,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_template::CheckTossRate<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -346,7 +597,12 @@ mod benches {
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
-		[pallet_sudo, Sudo]
+		[pallet_proxy, Proxy]
+		[pallet_preimage, Preimage]
+		[pallet_scheduler, Scheduler]
+		[pallet_collective, Council]
+		[pallet_membership, CouncilMembership]
+		[pallet_treasury, Treasury]
 		[pallet_template, TemplateModule]
 	);
 }
@@ -475,6 +731,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template::risk::CoinFlipperRiskApi<Block, Balance> for Runtime {
+		fn max_bet() -> Balance {
+			TemplateModule::max_bet()
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
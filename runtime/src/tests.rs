@@ -0,0 +1,337 @@
+use crate::*;
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo};
+use sp_runtime::{
+	traits::{BadOrigin, Hash},
+	DispatchError,
+};
+
+const ALICE: AccountId = AccountId::new([1u8; 32]);
+const BOB: AccountId = AccountId::new([2u8; 32]);
+const CHARLIE: AccountId = AccountId::new([3u8; 32]);
+
+// Build genesis storage with funded Alice and Bob, and a council of Alice, Bob and Charlie
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(ALICE, 1_000 * EXISTENTIAL_DEPOSIT), (BOB, 1_000 * EXISTENTIAL_DEPOSIT)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	pallet_membership::GenesisConfig::<Runtime, pallet_membership::Instance1> {
+		members: vec![ALICE, BOB, CHARLIE].try_into().unwrap(),
+		..Default::default()
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn toss_call() -> RuntimeCall {
+	RuntimeCall::TemplateModule(pallet_template::Call::do_toss {})
+}
+
+fn bet_call() -> RuntimeCall {
+	RuntimeCall::TemplateModule(pallet_template::Call::place_bet {
+		side: pallet_template::CoinSide::Head,
+		stake: 1,
+	})
+}
+
+fn schedule_call() -> RuntimeCall {
+	RuntimeCall::TemplateModule(pallet_template::Call::schedule_tosses { every: 1, count: 1 })
+}
+
+fn approve_call() -> RuntimeCall {
+	RuntimeCall::TemplateModule(pallet_template::Call::approve_operator {
+		who: CHARLIE,
+		expiry: None,
+	})
+}
+
+fn transfer_call() -> RuntimeCall {
+	RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death {
+		dest: BOB.into(),
+		value: EXISTENTIAL_DEPOSIT,
+	})
+}
+
+// Dispatches `call` as `real` through its proxy `delegate`, and returns the result of the call
+fn proxy(delegate: &AccountId, real: &AccountId, call: RuntimeCall) -> Result<(), DispatchError> {
+	assert_ok!(Proxy::proxy(
+		RuntimeOrigin::signed(delegate.clone()),
+		real.clone().into(),
+		None,
+		Box::new(call)
+	));
+	match System::events().last().map(|record| &record.event) {
+		Some(RuntimeEvent::Proxy(pallet_proxy::Event::ProxyExecuted { result })) => result.clone(),
+		event => panic!("Expected ProxyExecuted, got {:?}", event),
+	}
+}
+
+#[test]
+fn coin_game_proxy_filter_test() {
+	let set_proxy = RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+		delegate: BOB.into(),
+		proxy_type: ProxyType::Any,
+		delay: 0,
+	});
+
+	assert!(ProxyType::CoinGame.filter(&toss_call()));
+	assert!(ProxyType::CoinGame
+		.filter(&RuntimeCall::TemplateModule(pallet_template::Call::create_coin {})));
+	assert!(!ProxyType::CoinGame.filter(&transfer_call()));
+	assert!(!ProxyType::CoinGame.filter(&RuntimeCall::TemplateModule(
+		pallet_template::Call::deposit_bankroll { amount: 1 }
+	)));
+	assert!(!ProxyType::CoinGame.filter(&bet_call()));
+	assert!(!ProxyType::CoinGame.filter(&schedule_call()));
+	assert!(!ProxyType::CoinGame.filter(&approve_call()));
+	assert!(!ProxyType::CoinGame.filter(&set_proxy));
+
+	assert!(ProxyType::NonTransfer.filter(&toss_call()));
+	assert!(!ProxyType::NonTransfer.filter(&transfer_call()));
+	assert!(!ProxyType::NonTransfer.filter(&bet_call()));
+	assert!(!ProxyType::NonTransfer.filter(&schedule_call()));
+	assert!(ProxyType::NonTransfer.filter(&approve_call()));
+	assert!(!ProxyType::NonTransfer.filter(&RuntimeCall::TemplateModule(
+		pallet_template::Call::withdraw_bankroll { shares: 1 }
+	)));
+
+	assert!(ProxyType::Any.filter(&transfer_call()));
+}
+
+#[test]
+fn proxy_type_supersets_test() {
+	assert!(ProxyType::Any.is_superset(&ProxyType::CoinGame));
+	assert!(ProxyType::Any.is_superset(&ProxyType::NonTransfer));
+	assert!(ProxyType::NonTransfer.is_superset(&ProxyType::CoinGame));
+	assert!(!ProxyType::CoinGame.is_superset(&ProxyType::NonTransfer));
+	assert!(!ProxyType::CoinGame.is_superset(&ProxyType::Any));
+	assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::Any));
+}
+
+#[test]
+fn coin_game_proxy_tosses_but_cannot_move_funds_test() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(Proxy::add_proxy(
+			RuntimeOrigin::signed(ALICE),
+			BOB.into(),
+			ProxyType::CoinGame,
+			0
+		));
+
+		assert_ok!(proxy(&BOB, &ALICE, toss_call()));
+		assert_eq!(pallet_template::TossNonce::<Runtime>::get(&ALICE), 1);
+
+		let balance = Balances::free_balance(&ALICE);
+		assert_eq!(
+			proxy(&BOB, &ALICE, transfer_call()),
+			Err(frame_system::Error::<Runtime>::CallFiltered.into())
+		);
+		assert_eq!(Balances::free_balance(&ALICE), balance);
+
+		// The session key cannot widen its own permissions either
+		let add_any = RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+			delegate: BOB.into(),
+			proxy_type: ProxyType::Any,
+			delay: 0,
+		});
+		assert_eq!(
+			proxy(&BOB, &ALICE, add_any),
+			Err(frame_system::Error::<Runtime>::CallFiltered.into())
+		);
+	});
+}
+
+#[test]
+fn coin_game_proxy_cannot_change_player_limits_test() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(TemplateModule::self_exclude(RuntimeOrigin::signed(ALICE), 10));
+		assert_ok!(TemplateModule::set_loss_limits(RuntimeOrigin::signed(ALICE), Some(100), None));
+		assert_ok!(Proxy::add_proxy(
+			RuntimeOrigin::signed(ALICE),
+			BOB.into(),
+			ProxyType::CoinGame,
+			0
+		));
+		System::set_block_number(20);
+
+		let limits = pallet_template::PlayerLimitsOf::<Runtime>::get(&ALICE);
+		for call in [
+			pallet_template::Call::lift_self_exclusion {},
+			pallet_template::Call::set_loss_limits { daily: None, weekly: None },
+			pallet_template::Call::cool_off { duration: 10 },
+			pallet_template::Call::self_exclude { duration: 10 },
+		] {
+			assert_eq!(
+				proxy(&BOB, &ALICE, RuntimeCall::TemplateModule(call)),
+				Err(frame_system::Error::<Runtime>::CallFiltered.into())
+			);
+		}
+		assert_eq!(pallet_template::PlayerLimitsOf::<Runtime>::get(&ALICE), limits);
+
+		// The account itself still can
+		assert_ok!(TemplateModule::lift_self_exclusion(RuntimeOrigin::signed(ALICE)));
+	});
+}
+
+#[test]
+fn coin_game_and_non_transfer_proxies_cannot_schedule_or_delegate_test() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(Proxy::add_proxy(
+			RuntimeOrigin::signed(ALICE),
+			BOB.into(),
+			ProxyType::CoinGame,
+			0
+		));
+		assert_ok!(Proxy::add_proxy(
+			RuntimeOrigin::signed(ALICE),
+			CHARLIE.into(),
+			ProxyType::NonTransfer,
+			0
+		));
+		let balance = Balances::free_balance(&ALICE);
+
+		// Schedules charge their fee to the account, whichever proxy asks for them
+		for delegate in [&BOB, &CHARLIE] {
+			assert_eq!(
+				proxy(delegate, &ALICE, schedule_call()),
+				Err(frame_system::Error::<Runtime>::CallFiltered.into())
+			);
+		}
+		assert!(pallet_template::TossSchedules::<Runtime>::get(&ALICE).is_empty());
+		assert_eq!(Balances::free_balance(&ALICE), balance);
+
+		// A session key cannot approve other accounts to play with the coin
+		assert_eq!(
+			proxy(&BOB, &ALICE, approve_call()),
+			Err(frame_system::Error::<Runtime>::CallFiltered.into())
+		);
+		assert!(TemplateModule::ensure_operator(&ALICE, &CHARLIE).is_err());
+	});
+}
+
+#[test]
+fn only_registered_proxies_act_for_the_account_test() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::create_coin(RuntimeOrigin::signed(ALICE)));
+
+		assert_noop!(
+			Proxy::proxy(RuntimeOrigin::signed(BOB), ALICE.into(), None, Box::new(toss_call())),
+			pallet_proxy::Error::<Runtime>::NotProxy
+		);
+	});
+}
+
+// Proposes `call` to the council, and closes the motion once `ayes` voted for it
+fn council_motion(call: RuntimeCall, ayes: &[AccountId]) {
+	let length = call.encoded_size() as u32;
+	let weight = call.get_dispatch_info().weight;
+	let hash = <Runtime as frame_system::Config>::Hashing::hash_of(&call);
+	let index = pallet_collective::ProposalCount::<Runtime, CouncilCollective>::get();
+
+	assert_ok!(Council::propose(RuntimeOrigin::signed(ALICE), 2, Box::new(call), length));
+	for member in ayes {
+		assert_ok!(Council::vote(RuntimeOrigin::signed(member.clone()), hash, index, true));
+	}
+	System::set_block_number(System::block_number() + CouncilMotionDuration::get());
+	assert_ok!(Council::close(RuntimeOrigin::signed(ALICE), hash, index, weight, length));
+}
+
+fn set_quota_call(calls: Option<u32>) -> RuntimeCall {
+	RuntimeCall::TemplateModule(pallet_template::Call::set_sponsorship_quota { calls })
+}
+
+#[test]
+fn council_majority_sets_coin_flipper_parameters_test() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Council::members(), vec![ALICE, BOB, CHARLIE]);
+
+		council_motion(set_quota_call(Some(3)), &[ALICE, BOB]);
+		assert_eq!(pallet_template::SponsorshipQuota::<Runtime>::get(), Some(3));
+		assert_eq!(TemplateModule::sponsorship_quota(), 3);
+	});
+}
+
+#[test]
+fn council_minority_cannot_set_coin_flipper_parameters_test() {
+	new_test_ext().execute_with(|| {
+		// One aye out of three is not a majority, the motion is disapproved
+		council_motion(set_quota_call(Some(3)), &[ALICE]);
+		assert_eq!(pallet_template::SponsorshipQuota::<Runtime>::get(), None);
+
+		// Neither root nor single accounts can bypass the council
+		assert_noop!(
+			TemplateModule::set_sponsorship_quota(RuntimeOrigin::root(), Some(3)),
+			BadOrigin
+		);
+		assert_noop!(
+			TemplateModule::set_sponsorship_quota(RuntimeOrigin::signed(ALICE), Some(3)),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn only_the_council_changes_its_members_test() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CouncilMembership::reset_members(RuntimeOrigin::root(), vec![ALICE]),
+			BadOrigin
+		);
+		assert_noop!(
+			CouncilMembership::remove_member(RuntimeOrigin::root(), BOB.into()),
+			BadOrigin
+		);
+		assert_noop!(Council::set_members(RuntimeOrigin::root(), vec![ALICE], None, 0), BadOrigin);
+
+		council_motion(
+			RuntimeCall::CouncilMembership(pallet_membership::Call::remove_member {
+				who: CHARLIE.into(),
+			}),
+			&[ALICE, BOB],
+		);
+		assert_eq!(Council::members(), vec![ALICE, BOB]);
+	});
+}
+
+fn schedule_remark_call(when: BlockNumber) -> RuntimeCall {
+	RuntimeCall::Scheduler(pallet_scheduler::Call::schedule {
+		when,
+		maybe_periodic: None,
+		priority: 0,
+		call: Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] })),
+	})
+}
+
+#[test]
+fn only_a_council_majority_schedules_calls_test() {
+	new_test_ext().execute_with(|| {
+		let when = System::block_number() + CouncilMotionDuration::get() + 10;
+
+		assert_noop!(
+			Scheduler::schedule(
+				RuntimeOrigin::signed(ALICE),
+				when,
+				None,
+				0,
+				Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] })),
+			),
+			BadOrigin
+		);
+
+		council_motion(schedule_remark_call(when), &[ALICE]);
+		assert!(pallet_scheduler::Agenda::<Runtime>::get(when).is_empty());
+
+		council_motion(schedule_remark_call(when), &[ALICE, BOB]);
+		assert_eq!(pallet_scheduler::Agenda::<Runtime>::get(when).len(), 1);
+	});
+}